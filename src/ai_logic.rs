@@ -1,4 +1,9 @@
-use crate::types::{check_winner, Board, Player};
+use crate::config::{
+    FULL_MOVE_GENERATION_MAX_SIZE, HARD_AI_DEPTH, LARGE_BOARD_AI_DEPTH_CAP,
+    MEDIUM_BOARD_AI_DEPTH_CAP,
+};
+use crate::search::{best_move, Game, Outcome, SearchBoard};
+use crate::types::{check_winner, Board, Player, Topology};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(dead_code)]
@@ -7,137 +12,178 @@ pub struct BoardMove {
     pub col: usize,
 }
 
-fn minimax(
-    board: &mut Board,
-    depth: i32,
-    mut alpha: i32,
-    mut beta: i32,
-    maximizing_player: bool,
-) -> i32 {
-    // Check for terminal states
-    if let Some(winner) = check_winner(board) {
-        return match winner {
-            Player::O => 10 - depth,
-            Player::X => depth - 10,
+// Heuristic weight of a line holding `count` marks of a single player:
+// 1 for one mark, 10 for two, 100 for three and so on.
+pub fn line_score(count: u32) -> i32 {
+    10_i32.pow(count - 1)
+}
+
+// Heuristic score of a non-terminal board from Player::O's point of view.
+// Lines still open to only one player count towards that player.
+pub fn evaluate_board(board: &Board) -> i32 {
+    let mut score = 0;
+    for line in board.lines() {
+        let mut o_count = 0;
+        let mut x_count = 0;
+        for &(row, col) in line.cells.iter() {
+            match board[row][col] {
+                Some(Player::O) => o_count += 1,
+                Some(Player::X | Player::Triangle) => x_count += 1,
+                None => {}
+            }
+        }
+
+        score += match (o_count, x_count) {
+            (0, 0) => 0,
+            (o, 0) => line_score(o),
+            (0, x) => -line_score(x),
+            _ => 0,
         };
     }
+    score
+}
 
-    if crate::types::is_board_full(board) {
-        return 0;
+// Moves worth searching. Small boards try every empty cell; on larger boards
+// only cells touching an existing mark are considered (or the centre of an
+// empty board), which keeps the branching factor manageable. On a torus cells
+// touch across the edges too.
+pub fn candidate_moves(board: &Board) -> Vec<(usize, usize)> {
+    let (rows, cols) = board.dimensions();
+    if rows.max(cols) <= FULL_MOVE_GENERATION_MAX_SIZE {
+        return board.empty_cells().collect();
+    }
+    // An empty board opens as close to the centre as blocked cells allow
+    if board.is_empty() {
+        let centre_distance =
+            |&(row, col): &(usize, usize)| row.abs_diff(rows / 2) + col.abs_diff(cols / 2);
+        return board
+            .empty_cells()
+            .min_by_key(centre_distance)
+            .into_iter()
+            .collect();
     }
 
-    if maximizing_player {
-        // AI (Player::O) is maximizing
-        let mut max_eval = i32::MIN;
-        for row_idx in 0..3 {
-            for col_idx in 0..3 {
-                if board[row_idx][col_idx].is_none() {
-                    board[row_idx][col_idx] = Some(Player::O);
-                    let eval = minimax(board, depth + 1, alpha, beta, false);
-                    board[row_idx][col_idx] = None;
-                    max_eval = max_eval.max(eval);
-                    alpha = alpha.max(eval);
-                    if beta <= alpha {
-                        break; // Alpha-beta pruning
-                    }
-                }
-            }
-            if beta <= alpha {
-                break; // Alpha-beta pruning for outer loop
-            }
+    let wraps = board.topology() == Topology::Torus;
+    board
+        .empty_cells()
+        .filter(|&(row, col)| {
+            nearby(row, rows, wraps)
+                .any(|r| nearby(col, cols, wraps).any(|c| board[r][c].is_some()))
+        })
+        .collect()
+}
+
+// The index itself and those next to it on an axis of `len` cells.
+fn nearby(index: usize, len: usize, wraps: bool) -> impl Iterator<Item = usize> {
+    (-1..=1).filter_map(move |offset: isize| {
+        let near = index as isize + offset;
+        if wraps {
+            Some(near.rem_euclid(len as isize) as usize)
+        } else {
+            (0..len as isize).contains(&near).then_some(near as usize)
         }
-        max_eval
-    } else {
-        // Human (Player::X) is minimizing
-        let mut min_eval = i32::MAX;
-        for row_idx in 0..3 {
-            for col_idx in 0..3 {
-                if board[row_idx][col_idx].is_none() {
-                    board[row_idx][col_idx] = Some(Player::X);
-                    let eval = minimax(board, depth + 1, alpha, beta, true);
-                    board[row_idx][col_idx] = None;
-                    min_eval = min_eval.min(eval);
-                    beta = beta.min(eval);
-                    if beta <= alpha {
-                        break; // Alpha-beta pruning
-                    }
-                }
-            }
-            if beta <= alpha {
-                break; // Alpha-beta pruning for outer loop
-            }
+    })
+}
+
+// The deepest search that stays responsive on this board.
+pub fn depth_cap(board: &Board) -> i32 {
+    let (rows, cols) = board.dimensions();
+    match rows.max(cols) {
+        0..=3 => HARD_AI_DEPTH,
+        4 => MEDIUM_BOARD_AI_DEPTH_CAP,
+        _ => LARGE_BOARD_AI_DEPTH_CAP,
+    }
+}
+
+// A standard game as the shared search sees it: O maximizes, and only the
+// cells `candidate_moves` picks are searched.
+pub struct StandardGame {
+    search_board: SearchBoard,
+    to_move: Player,
+    misere: bool,
+}
+
+impl StandardGame {
+    pub fn new(board: &Board, to_move: Player, misere: bool) -> Self {
+        StandardGame {
+            search_board: SearchBoard::new(board),
+            to_move,
+            misere,
         }
-        min_eval
     }
 }
 
-pub fn get_best_move(board: &Board, player: Player) -> Option<(usize, usize)> {
-    println!("🤖 AI analyzing board for player {:?}:", player);
-
-    let mut best_score = if player == Player::O {
-        i32::MIN
-    } else {
-        i32::MAX
-    };
-    let mut best_move = None;
-    let mut board_copy = *board;
-
-    for row_idx in 0..3 {
-        for col_idx in 0..3 {
-            if board_copy[row_idx][col_idx].is_none() {
-                board_copy[row_idx][col_idx] = Some(player);
-
-                let score = if player == Player::O {
-                    // AI (Player::O) is maximizing
-                    minimax(&mut board_copy, 0, i32::MIN, i32::MAX, false)
-                } else {
-                    // Human/AI (Player::X) is minimizing
-                    minimax(&mut board_copy, 0, i32::MIN, i32::MAX, true)
-                };
-
-                board_copy[row_idx][col_idx] = None;
-
-                println!("   Move ({}, {}) -> Score: {}", row_idx, col_idx, score);
-
-                let is_better = if player == Player::O {
-                    score > best_score
-                } else {
-                    score < best_score
-                };
-
-                if is_better {
-                    best_score = score;
-                    best_move = Some((row_idx, col_idx));
-                    println!(
-                        "   ✅ New best move: ({}, {}) with score {}",
-                        row_idx, col_idx, score
-                    );
-                }
-            }
+impl Game for StandardGame {
+    type Move = (usize, usize);
+
+    const NAME: &'static str = "Standard";
+
+    fn maximizing(&self) -> bool {
+        self.to_move == Player::O
+    }
+
+    fn legal_moves(&self) -> Vec<(usize, usize)> {
+        self.search_board.board.empty_cells().collect()
+    }
+
+    fn search_moves(&self) -> Vec<(usize, usize)> {
+        candidate_moves(&self.search_board.board)
+    }
+
+    fn apply(&mut self, cell: (usize, usize)) {
+        self.search_board.place(cell, self.to_move);
+        self.to_move = self.to_move.opposite();
+    }
+
+    fn undo(&mut self, cell: (usize, usize)) {
+        self.search_board.take_back(cell);
+        self.to_move = self.to_move.opposite();
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        if let Some(line) = self.search_board.completed_line() {
+            return Some(Outcome::Win(line.player == Player::O));
         }
+        self.search_board.is_full().then_some(Outcome::Draw)
+    }
+
+    fn evaluate(&self) -> i32 {
+        evaluate_board(&self.search_board.board)
     }
 
-    println!(
-        "🎯 Final decision: {:?} with score {}",
-        best_move, best_score
-    );
-    best_move
+    fn misere(&self) -> bool {
+        self.misere
+    }
+
+    fn depth_cap(&self) -> i32 {
+        depth_cap(&self.search_board.board)
+    }
+}
+
+// Deepest search the board allows: never loses on 3×3.
+#[allow(dead_code)]
+pub fn get_best_move(board: &Board, player: Player) -> Option<(usize, usize)> {
+    get_best_move_with_depth(board, player, HARD_AI_DEPTH, false)
+}
+
+// Searches `max_depth` plies ahead (the candidate move counts as the first ply),
+// capped by the board size, and scores unfinished positions below that with
+// `evaluate_board`. With `misere` set, completing a line loses.
+pub fn get_best_move_with_depth(
+    board: &Board,
+    player: Player,
+    max_depth: i32,
+    misere: bool,
+) -> Option<(usize, usize)> {
+    best_move(&mut StandardGame::new(board, player, misere), max_depth).map(|(cell, _)| cell)
 }
 
 #[allow(dead_code)]
 pub fn find_empty_cells(board: &Board) -> Vec<BoardMove> {
-    let mut empty_cells = Vec::new();
-    for (row_idx, row) in board.iter().enumerate() {
-        for (col_idx, cell) in row.iter().enumerate() {
-            if cell.is_none() {
-                empty_cells.push(BoardMove {
-                    row: row_idx,
-                    col: col_idx,
-                });
-            }
-        }
-    }
-    empty_cells
+    board
+        .empty_cells()
+        .map(|(row, col)| BoardMove { row, col })
+        .collect()
 }
 
 #[allow(dead_code)]
@@ -147,8 +193,8 @@ pub fn get_game_result(board: &Board) -> Option<Player> {
 
 #[allow(dead_code)]
 impl BoardMove {
-    pub fn new(row: usize, col: usize) -> Option<Self> {
-        if row < 3 && col < 3 {
+    pub fn new(row: usize, col: usize, board_size: usize) -> Option<Self> {
+        if row < board_size && col < board_size {
             Some(BoardMove { row, col })
         } else {
             None
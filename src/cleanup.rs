@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+
+use crate::resources::{
+    AIDelay, BoardState, CurrentGameMode, CurrentRules, CurrentSetup, SeatAssignment,
+};
+
+// Despawns every entity (and its children) tagged with the component `T`.
+pub fn despawn_with<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// Clears the board state when a new series starts from the menu, starting it
+// from the selected setup. Cells blocked by hand belong to the last series.
+pub fn reset_game_state(
+    mut board_state: ResMut<BoardState>,
+    seats: Res<SeatAssignment>,
+    rules: Res<CurrentRules>,
+    game_mode: Res<CurrentGameMode>,
+    mut setup: ResMut<CurrentSetup>,
+    mut ai_delay: ResMut<AIDelay>,
+) {
+    if !setup.0.blocked.is_empty() {
+        setup.0.blocked.clear();
+    }
+    *board_state = BoardState::new(rules.0, seats.first_player);
+    let weaker = seats.weaker_player(game_mode.0, rules.0, seats.first_player);
+    board_state.apply_setup(&setup.0, weaker, &mut rand::thread_rng());
+    ai_delay.timer.reset();
+}
@@ -1,8 +1,9 @@
-use crate::types::Player;
+use crate::gobblet::PieceSize;
+use crate::types::{Player, Topology, TurnOrder};
 use bevy::prelude::*;
 
+// Marks every sprite that belongs to the board so it can be despawned with it.
 #[derive(Component)]
-#[allow(dead_code)]
 pub struct GameBoard;
 
 #[derive(Component)]
@@ -15,11 +16,21 @@ pub struct BoardPosition {
     pub col: usize,
 }
 
+// The symbol drawn in a cell, and the piece size in Gobblet Gobblers.
 #[derive(Component)]
-pub struct CellMark(#[allow(dead_code)] pub Player);
+#[allow(dead_code)]
+pub struct CellMark(pub Player, pub Option<PieceSize>);
 
+// A mark still falling into its cell in gravity games, starting `drop_height`
+// above it.
+#[derive(Component)]
+pub struct FallingMark {
+    pub drop_height: f32,
+    pub timer: Timer,
+}
+
+// Marks UI shown alongside the board (e.g. the Back button).
 #[derive(Component)]
-#[allow(dead_code)]
 pub struct GameUI;
 
 #[derive(Component)]
@@ -30,34 +41,146 @@ pub struct StatusText;
 pub struct RestartButton;
 
 #[derive(Component)]
-#[allow(dead_code)]
 pub struct BackButton;
 
 #[derive(Component)]
-#[allow(dead_code)]
+pub struct UndoButton;
+
+#[derive(Component)]
+pub struct RedoButton;
+
+// Takes over the opening move under the swap rule.
+#[derive(Component)]
+pub struct SwapButton;
+
+#[derive(Component)]
 pub struct DifficultyButton(pub crate::types::Difficulty);
 
 #[derive(Component)]
-#[allow(dead_code)]
 pub struct GameModeButton(pub crate::types::GameMode);
 
+// Side the human plays in Human vs AI.
+#[derive(Component)]
+pub struct SeatButton(pub Player);
+
+// Side that moves first.
+#[derive(Component)]
+pub struct FirstPlayerButton(pub Player);
+
+// Whether the first move alternates between games.
+#[derive(Component)]
+pub struct AlternateFirstButton(pub bool);
+
+// Cycles through the board size presets; its label shows the current one.
+#[derive(Component)]
+pub struct BoardSizeButton;
+
+// Cycles through the game variants; its label shows the current one.
+#[derive(Component)]
+pub struct VariantButton;
+
+// The symbol a human places next in wild games.
+#[derive(Component)]
+pub struct SymbolButton(pub Player);
+
+// A size on the Gobblet Gobblers reserve tray.
+#[derive(Component)]
+pub struct SizeButton(pub PieceSize);
+
+// Whether completing a line loses (misère) or wins.
+#[derive(Component)]
+pub struct MisereButton(pub bool);
+
+// Turns the given edges on and off, back to a flat board.
+#[derive(Component)]
+pub struct TopologyButton(pub Topology);
+
+// Who follows whom in three-player games.
+#[derive(Component)]
+pub struct TurnOrderButton(pub TurnOrder);
+
+// Turns the swap rule on and off.
+#[derive(Component)]
+pub struct SwapRuleButton;
+
+// Cycles through the number of cells blocked at random; its label shows it.
+#[derive(Component)]
+pub struct BlockedCellsButton;
+
+// Cycles through the number of handicap marks; its label shows it.
+#[derive(Component)]
+pub struct HandicapButton;
+
+// Menu buttons that show a selected state instead of plain hover feedback.
+#[derive(Component)]
+pub struct OptionButton;
+
+#[derive(Component)]
+pub struct StartButton;
+
+#[derive(Component)]
+pub struct ExportButton;
+
+// Root node of the main menu screen.
+#[derive(Component)]
+pub struct MainMenuUI;
+
 #[derive(Component)]
 pub struct GameOverMessage;
 
+// Strike-through and cell tint drawn over the winning line.
+#[derive(Component)]
+pub struct WinHighlight;
+
+// Tint over one Ultimate sub-board showing whether it is won, drawn or
+// where the next move must go.
+#[derive(Component)]
+pub struct SubBoardOverlay(pub usize);
+
+// Grey tint over one Notakto board once it is dead.
+#[derive(Component)]
+pub struct DeadBoardOverlay(pub usize);
+
+// Spooky marks, move numbers and collapse tints drawn over a quantum game.
+#[derive(Component)]
+pub struct QuantumMark;
+
+// Square drawn over a cell nobody may play in.
+#[derive(Component)]
+pub struct BlockedCell;
+
+// Tint behind the mark picked up to be moved in the sliding variant.
+#[derive(Component)]
+pub struct SelectedMarkHighlight;
+
 #[derive(Component)]
 #[allow(dead_code)]
 pub struct AIPlayer {
     pub difficulty: crate::types::Difficulty,
 }
 
+// Text showing whose move it is.
 #[derive(Component)]
-#[allow(dead_code)]
 pub struct PlayerTurnIndicator;
 
+// Text showing the GameStats tallies.
+#[derive(Component)]
+pub struct ScoreboardText;
+
+// Text showing the current mode and difficulty.
+#[derive(Component)]
+pub struct GameInfoText;
+
+// Text drawn in the theme's line color; recolored when the theme changes.
+#[derive(Component)]
+pub struct ThemedText;
+
+#[derive(Component)]
+pub struct ThemeButton(pub crate::types::Theme);
+
 #[derive(Component)]
 #[allow(dead_code)]
 pub struct GameTimer;
 
 #[derive(Component)]
-#[allow(dead_code)]
 pub struct MainMenuButton;
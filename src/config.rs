@@ -1,15 +1,25 @@
 use bevy::prelude::Color;
 
+use crate::types::Theme;
+
 // Board Layout
 pub const LINE_THICKNESS: f32 = 5.0;
 pub const BOARD_SIZE: f32 = 300.0;
-pub const CELL_SIZE: f32 = BOARD_SIZE / 3.0;
+pub const CELL_SIZE: f32 = BOARD_SIZE / 3.0; // Largest cell; bigger boards shrink cells to fit
+pub const MAX_BOARD_PIXELS: f32 = 360.0;
+// Qubic draws its four layers side by side, left of the scoreboard
+pub const LAYERED_BOARD_PIXELS: f32 = 540.0;
+pub const LAYERED_BOARD_CENTER_X: f32 = -110.0;
+pub const LAYER_GAP_RATIO: f32 = 0.75; // Gap between layers, in cells
 
 // Colors
 pub const BACKGROUND_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
 pub const LINE_COLOR: Color = Color::rgb(0.2, 0.2, 0.2);
 pub const X_COLOR: Color = Color::rgb(0.8, 0.2, 0.2);
 pub const O_COLOR: Color = Color::rgb(0.2, 0.2, 0.8);
+pub const TRIANGLE_COLOR: Color = Color::rgb(0.1, 0.6, 0.25);
+pub const DARK_BACKGROUND_COLOR: Color = Color::rgb(0.12, 0.12, 0.15);
+pub const DARK_LINE_COLOR: Color = Color::rgb(0.85, 0.85, 0.85);
 
 // Positioning
 pub const WINDOW_WIDTH: f32 = 800.0;
@@ -20,30 +30,58 @@ pub const BOARD_OFFSET_X: f32 = 0.0;
 pub const BOARD_OFFSET_Y: f32 = 50.0;
 
 // Marker styling
-#[allow(dead_code)]
 pub const MARKER_THICKNESS: f32 = 8.0;
 pub const MARKER_SIZE_RATIO: f32 = 0.7;
 
 // Timing
-#[allow(dead_code)]
 pub const AI_MOVE_DELAY: f32 = 1.0; // Seconds before AI moves
-#[allow(dead_code)]
 pub const ANIMATION_DURATION: f32 = 0.3;
 #[allow(dead_code)]
 pub const GAME_OVER_DISPLAY_TIME: f32 = 3.0;
 
 // AI difficulty settings
-#[allow(dead_code)]
 pub const EASY_AI_RANDOM_CHANCE: f32 = 0.8; // 80% random moves
-#[allow(dead_code)]
+pub const EASY_AI_DEPTH: i32 = 1; // Only sees immediate wins when not playing randomly
 pub const MEDIUM_AI_DEPTH: i32 = 3;
-#[allow(dead_code)]
-pub const HARD_AI_DEPTH: i32 = 9; // Full depth
+pub const HARD_AI_DEPTH: i32 = 9; // Full depth on 3×3
+
+// Search limits on boards larger than 3×3, where full minimax is infeasible
+pub const MEDIUM_BOARD_AI_DEPTH_CAP: i32 = 5; // 4×4
+pub const LARGE_BOARD_AI_DEPTH_CAP: i32 = 3; // 5×5 and up
+pub const ULTIMATE_HARD_AI_DEPTH: i32 = 5; // Ultimate tic-tac-toe, see ultimate.rs
+pub const FULL_MOVE_GENERATION_MAX_SIZE: usize = 4; // Larger boards only search near existing marks
+pub const QUBIC_HARD_AI_DEPTH: i32 = 4; // Qubic, see qubic.rs
+pub const QUBIC_AI_BREADTH: usize = 12; // Most promising moves searched per ply in Qubic
+pub const GRAVITY_HARD_AI_DEPTH: i32 = 6; // Gravity drop mode, see gravity.rs
+pub const GRAVITY_WIDE_BOARD_MIN_COLUMNS: usize = 10;
+pub const GRAVITY_WIDE_BOARD_AI_DEPTH_CAP: i32 = 3;
+pub const THREE_MARKS_HARD_AI_DEPTH: i32 = 7; // Disappearing / sliding marks, see three_marks.rs
+pub const WILD_HARD_AI_DEPTH: i32 = 6; // Wild tic-tac-toe, see wild.rs
+pub const QUANTUM_HARD_AI_DEPTH: i32 = 4; // Quantum tic-tac-toe, see quantum.rs
+pub const ORDER_AND_CHAOS_HARD_AI_DEPTH: i32 = 4; // Order and Chaos, see order_chaos.rs
+pub const ORDER_AND_CHAOS_AI_BREADTH: usize = 12; // Most promising moves searched per ply
+pub const GOBBLET_HARD_AI_DEPTH: i32 = 4; // Gobblet Gobblers, see gobblet.rs
+pub const THREE_PLAYER_HARD_AI_DEPTH: i32 = 5; // Three-player games, see three_player.rs
 
 // Text styling
+pub const FONT_PATH: &str = "fonts/FiraSans-Bold.ttf";
 pub const FONT_SIZE_TITLE: f32 = 32.0;
-#[allow(dead_code)]
 pub const FONT_SIZE_STATUS: f32 = 24.0;
+pub const FONT_SIZE_BUTTON: f32 = 20.0;
+pub const FONT_SIZE_HUD: f32 = 18.0;
+
+// Scoreboard panel
+pub const HUD_WIDTH: f32 = 200.0;
+pub const HUD_BACKGROUND_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.6);
+pub const DARK_HUD_BACKGROUND_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.4);
+
+// Buttons
+pub const BUTTON_WIDTH: f32 = 180.0;
+pub const BUTTON_HEIGHT: f32 = 45.0;
+pub const BUTTON_COLOR: Color = Color::rgb(0.25, 0.25, 0.3);
+pub const BUTTON_HOVER_COLOR: Color = Color::rgb(0.35, 0.35, 0.45);
+pub const BUTTON_SELECTED_COLOR: Color = Color::rgb(0.2, 0.5, 0.3);
+pub const BUTTON_TEXT_COLOR: Color = Color::rgb(0.95, 0.95, 0.95);
 
 // Hover effects
 #[allow(dead_code)]
@@ -56,11 +94,48 @@ pub const CLICK_SCALE: f32 = 0.95;
 pub const GRID_PADDING: f32 = 20.0;
 
 // Game states
-#[allow(dead_code)]
 pub const WIN_HIGHLIGHT_COLOR: Color = Color::rgb(0.9, 0.9, 0.2);
+pub const WIN_LINE_THICKNESS: f32 = 10.0;
+
+// Ultimate tic-tac-toe sub-board overlays
+pub const LEGAL_BOARD_COLOR: Color = Color::rgba(0.2, 0.8, 0.3, 0.2);
+pub const DRAWN_BOARD_COLOR: Color = Color::rgba(0.5, 0.5, 0.5, 0.35);
+pub const WON_BOARD_ALPHA: f32 = 0.25;
+
+// Tint behind the mark picked up to be moved in the sliding variant
+pub const SELECTED_MARK_COLOR: Color = Color::rgba(1.0, 0.8, 0.2, 0.35);
+
+// Square over blocked cells, see GameSetup
+pub const BLOCKED_CELL_COLOR: Color = Color::rgba(0.35, 0.35, 0.4, 0.85);
+pub const BLOCKED_CELL_SIZE_RATIO: f32 = 0.9;
 
 // Additional constants for AI vs AI mode
-#[allow(dead_code)]
 pub const AI_VS_AI_MOVE_DELAY: f32 = 1.5; // Slower moves for visibility
 #[allow(dead_code)]
 pub const AI_THINKING_INDICATOR_DELAY: f32 = 0.5; // Show "thinking" indicator
+
+// Colors that follow the selected theme (text uses the grid line color).
+pub struct ThemeColors {
+    pub background: Color,
+    pub line: Color,
+    pub panel: Color,
+}
+
+pub const LIGHT_THEME: ThemeColors = ThemeColors {
+    background: BACKGROUND_COLOR,
+    line: LINE_COLOR,
+    panel: HUD_BACKGROUND_COLOR,
+};
+
+pub const DARK_THEME: ThemeColors = ThemeColors {
+    background: DARK_BACKGROUND_COLOR,
+    line: DARK_LINE_COLOR,
+    panel: DARK_HUD_BACKGROUND_COLOR,
+};
+
+pub fn theme_colors(theme: Theme) -> &'static ThemeColors {
+    match theme {
+        Theme::Light => &LIGHT_THEME,
+        Theme::Dark => &DARK_THEME,
+    }
+}
@@ -1,11 +1,17 @@
-use crate::types::Player;
+use crate::types::{MoveKind, Player, WinningLine};
 
 #[derive(Debug, Clone)]
 pub struct PlayerMoveEvent {
+    // The cell played; the opening cell when swapping.
     pub position: (usize, usize),
+    pub kind: MoveKind,
 }
 
 #[derive(Debug, Clone)]
 pub struct GameOverEvent {
     pub winner: Option<Player>,
+    // The completed line when the game was won, None for a draw.
+    pub winning_line: Option<WinningLine>,
+    // Quantum: the loser made a line in the same collapse and scores ½.
+    pub split: bool,
 }
@@ -0,0 +1,253 @@
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::components::ExportButton;
+use crate::resources::{GameStats, MatchHistory};
+use crate::types::{GameMode, GameRecord, MoveKind};
+
+const EXPORT_FILE_STEM: &str = "tic-tac-toe-stats";
+
+// Everything written by an export, in the shape of the JSON file.
+#[derive(Serialize)]
+pub struct ExportData<'a> {
+    pub exported_at: String,
+    pub stats: &'a GameStats,
+    pub games: &'a [GameRecord],
+}
+
+pub fn to_json(data: &ExportData) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(data)
+}
+
+// One row per game, followed by a blank line and the aggregate stats.
+pub fn to_csv(data: &ExportData) -> String {
+    let mut csv =
+        String::from("timestamp,mode,difficulty,human_player,first_player,rules,moves,result\n");
+    for game in data.games {
+        let moves = game
+            .moves
+            .iter()
+            .map(|m| {
+                // Gobblet pieces are listed with their size, e.g. "XL"
+                let mover = match m.kind {
+                    MoveKind::Gobblet { size, .. } => {
+                        format!("{}{}", m.player.to_char(), size.initial())
+                    }
+                    _ => m.player.to_char().to_string(),
+                };
+                match m.kind {
+                    // Wild games also list the symbol placed
+                    MoveKind::Wild { symbol } => {
+                        format!("{}:{}({},{})", mover, symbol.to_char(), m.row, m.col)
+                    }
+                    // Quantum games list both cells of a spooky mark
+                    MoveKind::Quantum {
+                        partner: (row, col),
+                    } => format!("{}({},{})+({},{})", mover, m.row, m.col, row, col),
+                    // Three-marks and Gobblet games also list the cell a piece left
+                    _ => match m.vacated() {
+                        Some((row, col)) => {
+                            format!("{}({},{})>({},{})", mover, row, col, m.row, m.col)
+                        }
+                        None => format!("{}({},{})", mover, m.row, m.col),
+                    },
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        // Difficulty and seats only matter when an AI played
+        let difficulty = if game.game_mode == GameMode::HumanVsHuman {
+            ""
+        } else {
+            game.difficulty.label()
+        };
+        let human_player = if game.game_mode == GameMode::HumanVsAI {
+            game.human_player.to_char().to_string()
+        } else {
+            String::new()
+        };
+
+        let fields = [
+            format_timestamp(game.timestamp),
+            game.game_mode.label().to_string(),
+            difficulty.to_string(),
+            human_player,
+            game.first_player.to_char().to_string(),
+            game.rules.label(),
+            moves,
+            game.result.label(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+
+    csv.push_str("\nx_wins,o_wins,triangle_wins,x_split_wins,o_split_wins,draws,total_games\n");
+    csv.push_str(&format!(
+        "{},{},{},{},{},{},{}\n",
+        data.stats.x_wins,
+        data.stats.o_wins,
+        data.stats.triangle_wins,
+        data.stats.x_split_wins,
+        data.stats.o_split_wins,
+        data.stats.draws,
+        data.stats.total_games
+    ));
+    csv
+}
+
+// Quotes a CSV field if it contains a separator, quote or newline.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Formats Unix seconds as an ISO 8601 UTC timestamp (e.g. 2024-01-31T18:05:00Z).
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds_of_day = timestamp % 86_400;
+
+    // Civil-from-days conversion (proleptic Gregorian calendar)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3_600,
+        seconds_of_day % 3_600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+// Current time in seconds since the Unix epoch.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn current_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+// Current time in seconds since the Unix epoch (std's clock panics on the web).
+#[cfg(target_arch = "wasm32")]
+pub fn current_timestamp() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+// Export with the E key or the menu's Export button.
+pub fn handle_export(
+    keys: Res<Input<KeyCode>>,
+    export_buttons: Query<&Interaction, (Changed<Interaction>, With<ExportButton>)>,
+    game_stats: Res<GameStats>,
+    match_history: Res<MatchHistory>,
+) {
+    let clicked = export_buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if !keys.just_pressed(KeyCode::E) && !clicked {
+        return;
+    }
+
+    let exported_at = format_timestamp(current_timestamp());
+    let data = ExportData {
+        exported_at: exported_at.clone(),
+        stats: &game_stats,
+        games: &match_history.0,
+    };
+    // File names avoid ':' which Windows does not allow
+    let file_stem = format!("{}-{}", EXPORT_FILE_STEM, exported_at.replace(':', "-"));
+
+    info!(
+        "📤 Exporting {} game(s) as {}.csv / .json",
+        data.games.len(),
+        file_stem
+    );
+
+    match to_json(&data) {
+        Ok(json) => save_export(&format!("{}.json", file_stem), &json, "application/json"),
+        Err(err) => warn!("⚠️ Could not serialize export: {}", err),
+    }
+    save_export(&format!("{}.csv", file_stem), &to_csv(&data), "text/csv");
+}
+
+fn save_export(file_name: &str, contents: &str, mime_type: &str) {
+    match output::save(file_name, contents, mime_type) {
+        Ok(location) => info!("✅ Exported {}", location),
+        Err(err) => warn!("⚠️ Could not export {}: {}", file_name, err),
+    }
+}
+
+// Desktop: files in the user's documents directory (or the working directory).
+#[cfg(not(target_arch = "wasm32"))]
+mod output {
+    use std::fs;
+    use std::path::PathBuf;
+
+    pub fn save(file_name: &str, contents: &str, _mime_type: &str) -> Result<String, String> {
+        let dir = dirs::document_dir().unwrap_or_else(|| PathBuf::from("."));
+        let path = dir.join(file_name);
+        fs::write(&path, contents).map_err(|err| err.to_string())?;
+        Ok(path.display().to_string())
+    }
+}
+
+// Web: a browser download of an in-memory Blob.
+#[cfg(target_arch = "wasm32")]
+mod output {
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::{JsCast, JsValue};
+    use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+
+    // How long the Blob URL outlives the click that starts the download.
+    const REVOKE_DELAY_MS: i32 = 1000;
+
+    pub fn save(file_name: &str, contents: &str, mime_type: &str) -> Result<String, String> {
+        download(file_name, contents, mime_type).map_err(|err| format!("{:?}", err))?;
+        Ok(format!("{} (download)", file_name))
+    }
+
+    fn download(file_name: &str, contents: &str, mime_type: &str) -> Result<(), JsValue> {
+        let window = web_sys::window().ok_or("no window")?;
+        let document = window.document().ok_or("no document")?;
+
+        let parts = js_sys::Array::of1(&JsValue::from_str(contents));
+        let options = BlobPropertyBag::new();
+        options.set_type(mime_type);
+        let blob = Blob::new_with_str_sequence_and_options(&parts, &options)?;
+        let url = Url::create_object_url_with_blob(&blob)?;
+
+        let anchor: HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+        anchor.set_href(&url);
+        anchor.set_download(file_name);
+        anchor.click();
+
+        // The download may not have started yet when click() returns, so the
+        // URL is revoked once the browser gets back to its event loop.
+        let revoke = Closure::once_into_js(move || {
+            let _ = Url::revoke_object_url(&url);
+        });
+        window.set_timeout_with_callback_and_timeout_and_arguments_0(
+            revoke.unchecked_ref(),
+            REVOKE_DELAY_MS,
+        )?;
+        Ok(())
+    }
+}
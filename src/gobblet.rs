@@ -0,0 +1,379 @@
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ai_logic::evaluate_board;
+use crate::config::GOBBLET_HARD_AI_DEPTH;
+use crate::search::{Game, Outcome};
+use crate::three_marks::REPETITION_DRAW_COUNT;
+use crate::types::{Board, BoardConfig, MoveKind, MoveRecord, Player, WinningLine};
+
+// Gobblet Gobblers on the classic board. Each player starts with two small, two
+// medium and two large pieces in reserve. A move places a piece from the
+// reserve, or moves one of the player's pieces already on top of a stack, onto
+// an empty cell or onto any smaller piece. Only the top piece of each stack
+// counts towards a line. Lifting a piece may uncover an opponent's line, and
+// that line wins for the opponent even if the move also made one of the
+// mover's own. As pieces can move back and forth, the same position coming up
+// a third time is a draw.
+//
+// `BoardState` keeps the owner of each top piece on its `Board`, next to the
+// stacks and reserves.
+
+pub const SIDE: usize = 3;
+pub const PIECES_PER_SIZE: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum PieceSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl PieceSize {
+    pub const ALL: [PieceSize; 3] = [PieceSize::Small, PieceSize::Medium, PieceSize::Large];
+
+    // Returns the name shown on the reserve tray.
+    pub fn label(self) -> &'static str {
+        match self {
+            PieceSize::Small => "Small",
+            PieceSize::Medium => "Medium",
+            PieceSize::Large => "Large",
+        }
+    }
+
+    // The first letter of the label, used in exports.
+    pub fn initial(self) -> char {
+        match self {
+            PieceSize::Small => 'S',
+            PieceSize::Medium => 'M',
+            PieceSize::Large => 'L',
+        }
+    }
+
+    // How much of a cell the piece's glyph fills.
+    pub fn scale(self) -> f32 {
+        match self {
+            PieceSize::Small => 0.4,
+            PieceSize::Medium => 0.6,
+            PieceSize::Large => 0.85,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Piece {
+    pub player: Player,
+    pub size: PieceSize,
+}
+
+// A move: a piece of `size` lands on `to`, from the reserve or, with `from`
+// set, lifted off the top of that cell's stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GobbletMove {
+    pub from: Option<(usize, usize)>,
+    pub size: PieceSize,
+    pub to: (usize, usize),
+}
+
+impl GobbletMove {
+    // The move a record stands for.
+    pub fn recorded(record: &MoveRecord) -> Self {
+        let MoveKind::Gobblet { size, from } = record.kind else {
+            unreachable!("not a Gobblet move: {:?}", record.kind);
+        };
+        GobbletMove {
+            from,
+            size,
+            to: (record.row, record.col),
+        }
+    }
+}
+
+// An empty classic board. The lines are built once and shared by every clone.
+fn empty_board() -> Board {
+    static EMPTY: OnceLock<Board> = OnceLock::new();
+    EMPTY
+        .get_or_init(|| Board::new(BoardConfig::CLASSIC))
+        .clone()
+}
+
+fn seat(player: Player) -> usize {
+    match player {
+        Player::X => 0,
+        Player::O => 1,
+        Player::Triangle => unreachable!("Gobblet Gobblers has two players"),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GobbletState {
+    // Pieces in each cell, bottom first; sizes grow towards the top.
+    stacks: [[Vec<Piece>; SIDE]; SIDE],
+    // Pieces left in reserve, by seat and size.
+    reserves: [[usize; 3]; 2],
+    to_move: Player,
+}
+
+impl GobbletState {
+    pub fn new(first_player: Player) -> Self {
+        GobbletState {
+            stacks: Default::default(),
+            reserves: [[PIECES_PER_SIZE; 3]; 2],
+            to_move: first_player,
+        }
+    }
+
+    pub fn to_move(&self) -> Player {
+        self.to_move
+    }
+
+    pub fn top(&self, row: usize, col: usize) -> Option<Piece> {
+        self.stacks[row][col].last().copied()
+    }
+
+    pub fn reserve(&self, player: Player, size: PieceSize) -> usize {
+        self.reserves[seat(player)][size.index()]
+    }
+
+    // The piece a move from `from` carries: the top piece there, or the
+    // largest one the player to move has left in reserve.
+    pub fn default_size(&self, from: Option<(usize, usize)>) -> Option<PieceSize> {
+        match from {
+            Some((row, col)) => self.top(row, col).map(|piece| piece.size),
+            None => PieceSize::ALL
+                .into_iter()
+                .rev()
+                .find(|&size| self.reserve(self.to_move, size) > 0),
+        }
+    }
+
+    // Whether a piece of `size` may land on the cell: it must be empty or
+    // topped by a smaller piece.
+    fn can_cover(&self, (row, col): (usize, usize), size: PieceSize) -> bool {
+        match self.top(row, col) {
+            Some(piece) => piece.size < size,
+            None => true,
+        }
+    }
+
+    pub fn is_legal(&self, gobblet_move: GobbletMove) -> bool {
+        let GobbletMove { from, size, to } = gobblet_move;
+        let available = match from {
+            None => self.reserve(self.to_move, size) > 0,
+            Some((row, col)) => {
+                from != Some(to)
+                    && self.top(row, col)
+                        == Some(Piece {
+                            player: self.to_move,
+                            size,
+                        })
+            }
+        };
+        available && self.can_cover(to, size)
+    }
+
+    pub fn legal_moves(&self) -> Vec<GobbletMove> {
+        let cells: Vec<_> = (0..SIDE)
+            .flat_map(|row| (0..SIDE).map(move |col| (row, col)))
+            .collect();
+        let mut moves = Vec::new();
+
+        // Larger pieces first: they are usually the stronger moves
+        for size in PieceSize::ALL.into_iter().rev() {
+            for &to in cells.iter() {
+                let gobblet_move = GobbletMove {
+                    from: None,
+                    size,
+                    to,
+                };
+                if self.is_legal(gobblet_move) {
+                    moves.push(gobblet_move);
+                }
+            }
+        }
+        for &from in cells.iter() {
+            let Some(piece) = self.top(from.0, from.1) else {
+                continue;
+            };
+            for &to in cells.iter() {
+                let gobblet_move = GobbletMove {
+                    from: Some(from),
+                    size: piece.size,
+                    to,
+                };
+                if self.is_legal(gobblet_move) {
+                    moves.push(gobblet_move);
+                }
+            }
+        }
+        moves
+    }
+
+    pub fn apply(&mut self, gobblet_move: GobbletMove) {
+        let GobbletMove { from, size, to } = gobblet_move;
+        let player = self.to_move;
+        match from {
+            Some((row, col)) => {
+                self.stacks[row][col].pop();
+            }
+            None => self.reserves[seat(player)][size.index()] -= 1,
+        }
+        self.stacks[to.0][to.1].push(Piece { player, size });
+        self.to_move = player.opposite();
+    }
+
+    // Takes back `gobblet_move`, the last move applied.
+    pub fn undo(&mut self, gobblet_move: GobbletMove) {
+        let GobbletMove { from, size, to } = gobblet_move;
+        let player = self.to_move.opposite();
+        self.stacks[to.0][to.1].pop();
+        match from {
+            Some((row, col)) => self.stacks[row][col].push(Piece { player, size }),
+            None => self.reserves[seat(player)][size.index()] += 1,
+        }
+        self.to_move = player;
+    }
+
+    // Owners of the top pieces as a board.
+    pub fn board(&self) -> Board {
+        let mut board = empty_board();
+        for row in 0..SIDE {
+            for col in 0..SIDE {
+                board[row][col] = self.top(row, col).map(|piece| piece.player);
+            }
+        }
+        board
+    }
+
+    // The winning line once the top pieces make one. If the last move left
+    // lines for both players, the one uncovered for the player now to move wins.
+    pub fn winning_line(&self) -> Option<WinningLine> {
+        let board = self.board();
+        let mut lines = board.lines().iter().filter_map(|line| {
+            let (row, col) = line.cells[0];
+            let player = board[row][col]?;
+            line.cells
+                .iter()
+                .all(|&(row, col)| board[row][col] == Some(player))
+                .then(|| WinningLine {
+                    player,
+                    kind: line.kind,
+                    cells: line.cells.clone(),
+                })
+        });
+        let first = lines.next()?;
+        if first.player == self.to_move {
+            return Some(first);
+        }
+        Some(
+            lines
+                .find(|line| line.player == self.to_move)
+                .unwrap_or(first),
+        )
+    }
+
+    // Identifies the position for repetition checks: for each cell, the owner
+    // of its piece of each size, and who is to move.
+    fn position_key(&self) -> u64 {
+        let mut key = seat(self.to_move) as u64;
+        for row in self.stacks.iter() {
+            for stack in row.iter() {
+                let mut slots = [0_u64; 3];
+                for piece in stack {
+                    slots[piece.size.index()] = seat(piece.player) as u64 + 1;
+                }
+                for slot in slots {
+                    key = (key << 2) | slot;
+                }
+            }
+        }
+        key
+    }
+}
+
+// Whether the game in `state`, reached by `moves`, has ended in a draw: no
+// line, and the position has come up `REPETITION_DRAW_COUNT` times or leaves
+// the player to move stuck.
+pub fn is_draw(state: &GobbletState, first_player: Player, moves: &[MoveRecord]) -> bool {
+    state.winning_line().is_none()
+        && (is_repetition_draw(first_player, moves) || state.legal_moves().is_empty())
+}
+
+// Whether the position reached by `moves` has now occurred
+// `REPETITION_DRAW_COUNT` times.
+fn is_repetition_draw(first_player: Player, moves: &[MoveRecord]) -> bool {
+    let mut state = GobbletState::new(first_player);
+    let mut history = vec![state.position_key()];
+    for record in moves {
+        state.apply(GobbletMove::recorded(record));
+        history.push(state.position_key());
+    }
+    let current = state.position_key();
+    history.iter().filter(|&&key| key == current).count() >= REPETITION_DRAW_COUNT
+}
+
+// A Gobblet game as the shared search sees it, with O maximizing. Reaching a
+// position already on the search path is scored as a draw.
+pub struct GobbletGame {
+    state: GobbletState,
+    // The positions before each move played, most recent last.
+    seen: Vec<u64>,
+    misere: bool,
+}
+
+impl GobbletGame {
+    pub fn new(state: &GobbletState, misere: bool) -> Self {
+        GobbletGame {
+            state: state.clone(),
+            seen: Vec::new(),
+            misere,
+        }
+    }
+}
+
+impl Game for GobbletGame {
+    type Move = GobbletMove;
+
+    const NAME: &'static str = "Gobblet";
+    const HARD_DEPTH: i32 = GOBBLET_HARD_AI_DEPTH;
+
+    fn maximizing(&self) -> bool {
+        self.state.to_move() == Player::O
+    }
+
+    fn legal_moves(&self) -> Vec<GobbletMove> {
+        self.state.legal_moves()
+    }
+
+    fn apply(&mut self, gobblet_move: GobbletMove) {
+        self.seen.push(self.state.position_key());
+        self.state.apply(gobblet_move);
+    }
+
+    fn undo(&mut self, gobblet_move: GobbletMove) {
+        self.state.undo(gobblet_move);
+        self.seen.pop();
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        if let Some(line) = self.state.winning_line() {
+            return Some(Outcome::Win(line.player == Player::O));
+        }
+        self.seen
+            .contains(&self.state.position_key())
+            .then_some(Outcome::Draw)
+    }
+
+    fn evaluate(&self) -> i32 {
+        evaluate_board(&self.state.board())
+    }
+
+    fn misere(&self) -> bool {
+        self.misere
+    }
+}
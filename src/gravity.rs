@@ -0,0 +1,112 @@
+use crate::ai_logic::evaluate_board;
+use crate::config::{
+    GRAVITY_HARD_AI_DEPTH, GRAVITY_WIDE_BOARD_AI_DEPTH_CAP, GRAVITY_WIDE_BOARD_MIN_COLUMNS,
+};
+use crate::search::{Game, Outcome, SearchBoard};
+use crate::types::{Board, Player};
+
+// Gravity (Connect Four-style) play: marks are dropped into a column and fall
+// to its lowest empty cell, so each column offers at most one move.
+
+// The row a mark dropped into `col` lands on, or None if the column is full.
+// A blocked cell acts as a floor: the mark comes to rest on top of it.
+pub fn drop_row(board: &Board, col: usize) -> Option<usize> {
+    let (rows, _) = board.dimensions();
+    let floor = (0..rows)
+        .find(|&row| board[row][col].is_some() || board.is_blocked(row, col))
+        .unwrap_or(rows);
+    floor.checked_sub(1)
+}
+
+// Whether a mark dropped into the cell's column lands there.
+pub fn is_legal_move(board: &Board, (row, col): (usize, usize)) -> bool {
+    drop_row(board, col) == Some(row)
+}
+
+// One landing cell per column that still has room, centre columns first:
+// they take part in the most lines, which helps alpha-beta prune early.
+pub fn legal_moves(board: &Board) -> Vec<(usize, usize)> {
+    let (_, cols) = board.dimensions();
+    let middle = cols / 2;
+    let mut columns: Vec<usize> = (0..cols).collect();
+    columns.sort_by_key(|&col| col.abs_diff(middle));
+    columns
+        .into_iter()
+        .filter_map(|col| drop_row(board, col).map(|row| (row, col)))
+        .collect()
+}
+
+// The deepest search that stays responsive; wide boards offer more moves per ply.
+fn depth_cap(board: &Board) -> i32 {
+    let (_, cols) = board.dimensions();
+    if cols >= GRAVITY_WIDE_BOARD_MIN_COLUMNS {
+        GRAVITY_WIDE_BOARD_AI_DEPTH_CAP
+    } else {
+        GRAVITY_HARD_AI_DEPTH
+    }
+}
+
+// A gravity game as the shared search sees it: O maximizes, and each move
+// drops a mark into a column.
+pub struct GravityGame {
+    search_board: SearchBoard,
+    to_move: Player,
+    misere: bool,
+}
+
+impl GravityGame {
+    pub fn new(board: &Board, to_move: Player, misere: bool) -> Self {
+        GravityGame {
+            search_board: SearchBoard::new(board),
+            to_move,
+            misere,
+        }
+    }
+}
+
+impl Game for GravityGame {
+    type Move = (usize, usize);
+
+    const NAME: &'static str = "Gravity";
+    const HARD_DEPTH: i32 = GRAVITY_HARD_AI_DEPTH;
+
+    fn maximizing(&self) -> bool {
+        self.to_move == Player::O
+    }
+
+    fn legal_moves(&self) -> Vec<(usize, usize)> {
+        legal_moves(&self.search_board.board)
+    }
+
+    fn apply(&mut self, cell: (usize, usize)) {
+        self.search_board.place(cell, self.to_move);
+        self.to_move = self.to_move.opposite();
+    }
+
+    fn undo(&mut self, cell: (usize, usize)) {
+        self.search_board.take_back(cell);
+        self.to_move = self.to_move.opposite();
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        if let Some(line) = self.search_board.completed_line() {
+            return Some(Outcome::Win(line.player == Player::O));
+        }
+        // Cells under a block can never be filled, so the board need not be full
+        legal_moves(&self.search_board.board)
+            .is_empty()
+            .then_some(Outcome::Draw)
+    }
+
+    fn evaluate(&self) -> i32 {
+        evaluate_board(&self.search_board.board)
+    }
+
+    fn misere(&self) -> bool {
+        self.misere
+    }
+
+    fn depth_cap(&self) -> i32 {
+        depth_cap(&self.search_board.board)
+    }
+}
@@ -1,25 +1,57 @@
 use bevy::prelude::*;
 
 mod ai_logic;
+mod cleanup;
 mod components;
 mod config;
 mod events;
+mod export;
+mod gobblet;
+mod gravity;
+mod notakto;
+mod order_chaos;
+mod persistence;
+mod quantum;
+mod qubic;
 mod resources;
+mod search;
+mod states;
+mod three_marks;
+mod three_player;
 mod types;
+mod ultimate;
+mod wild;
 
 #[cfg(test)]
 mod test;
 
+use cleanup::{despawn_with, reset_game_state};
+use components::{GameBoard, GameOverMessage, GameUI, MainMenuUI};
 use events::{GameOverEvent, PlayerMoveEvent};
-use resources::{AIDelay, BoardState, CurrentAIDifficulty, CurrentGameMode, GameStats};
-use types::{Difficulty, GameMode};
+use export::handle_export;
+use persistence::{load_saved_data, save_on_change};
+use resources::{AIDelay, BoardLayout, BoardState, SelectedMark, SelectedSize, SelectedSymbol};
+use states::{in_game, AppState};
 
 mod systems;
 use systems::ai::ai_make_move;
-use systems::gameplay::{apply_player_move, check_game_state};
-use systems::input::handle_mouse_clicks;
-use systems::setup::setup_game;
-use systems::ui::{display_game_over_ui, handle_restart_button};
+use systems::gameplay::{
+    animate_falling_marks, apply_player_move, check_game_state, update_gobblet_pieces,
+    update_quantum_marks,
+};
+use systems::history::{handle_undo_redo, record_completed_game};
+use systems::input::{handle_cell_blocking, handle_mouse_clicks};
+use systems::menu::{
+    handle_menu_buttons, handle_rules_buttons, setup_main_menu, update_menu_button_colors,
+    update_misere_button_visibility, update_rules_button_labels,
+};
+use systems::setup::{apply_theme, setup_camera, setup_game};
+use systems::ui::{
+    button_hover_colors, display_game_over_ui, handle_back_button, handle_reserve_tray,
+    handle_restart_button, handle_swap_button, handle_symbol_picker, setup_scoreboard,
+    update_dead_board_overlays, update_scoreboard, update_selected_mark_highlight,
+    update_sub_board_overlays,
+};
 
 fn main() {
     #[cfg(target_arch = "wasm32")]
@@ -45,19 +77,81 @@ fn main() {
         .add_event::<PlayerMoveEvent>()
         .add_event::<GameOverEvent>()
         .insert_resource(BoardState::default())
-        .insert_resource(CurrentGameMode(GameMode::HumanVsAI))
-        .insert_resource(CurrentAIDifficulty(Difficulty::Hard))
-        .insert_resource(GameStats::default())
         .insert_resource(AIDelay::default())
-        .add_startup_system(setup_game)
+        .insert_resource(BoardLayout::default())
+        .insert_resource(SelectedMark::default())
+        .insert_resource(SelectedSymbol::default())
+        .insert_resource(SelectedSize::default())
+        .add_state::<AppState>()
+        .add_startup_system(load_saved_data)
+        .add_startup_system(setup_camera)
         .add_systems((
-            // Just add the systems directly - Bevy handles parameters automatically
-            handle_mouse_clicks,
-            ai_make_move,
-            apply_player_move,
-            check_game_state,
-            display_game_over_ui,
-            handle_restart_button,
+            button_hover_colors,
+            apply_theme,
+            save_on_change,
+            handle_export,
         ))
+        // Main menu
+        .add_system(setup_main_menu.in_schedule(OnEnter(AppState::MainMenu)))
+        .add_systems(
+            (
+                despawn_with::<GameBoard>,
+                despawn_with::<GameUI>,
+                despawn_with::<GameOverMessage>,
+            )
+                .in_schedule(OnEnter(AppState::MainMenu)),
+        )
+        .add_systems(
+            (
+                handle_menu_buttons,
+                handle_rules_buttons,
+                update_menu_button_colors,
+                update_rules_button_labels,
+                update_misere_button_visibility,
+            )
+                .in_set(OnUpdate(AppState::MainMenu)),
+        )
+        .add_systems(
+            (
+                despawn_with::<MainMenuUI>,
+                reset_game_state,
+                setup_game.after(reset_game_state),
+                setup_scoreboard,
+            )
+                .in_schedule(OnExit(AppState::MainMenu)),
+        )
+        // Playing
+        .add_systems(
+            (
+                handle_mouse_clicks,
+                handle_cell_blocking,
+                ai_make_move,
+                apply_player_move,
+                check_game_state,
+                handle_undo_redo,
+                handle_symbol_picker,
+                handle_reserve_tray,
+                handle_swap_button,
+            )
+                .in_set(OnUpdate(AppState::Playing)),
+        )
+        // Game over
+        .add_systems(
+            (display_game_over_ui, record_completed_game).in_set(OnUpdate(AppState::GameOver)),
+        )
+        .add_systems(
+            (
+                handle_restart_button,
+                handle_back_button,
+                update_scoreboard,
+                update_sub_board_overlays,
+                update_dead_board_overlays,
+                update_selected_mark_highlight,
+                animate_falling_marks,
+                update_quantum_marks,
+                update_gobblet_pieces,
+            )
+                .distributive_run_if(in_game),
+        )
         .run();
 }
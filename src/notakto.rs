@@ -0,0 +1,393 @@
+use rand::Rng;
+
+use crate::search::{self, Game, Outcome, SearchBoard};
+use crate::types::{
+    find_winning_line_through, Board, Difficulty, Line, LineKind, MoveRecord, Player, WinningLine,
+};
+
+// Notakto: both players place X on one or more 3×3 boards. A board with three
+// in a row is dead and takes no more marks, and whoever kills the last board
+// loses. The boards are stored side by side on one 3×(3·n) `Board`, so cell
+// (row, col) of board `b` lives at (row, 3 * b + col).
+//
+// The AI plays perfectly using the misère quotient of Notakto (Plambeck and
+// Whitehead, "The Secrets of Notakto"): every board position has a value in
+// an 18-element monoid, the values of all boards multiply to the value of the
+// whole game, and the player to move loses exactly when that product is one
+// of four P-position values.
+
+// Cells along each edge of a board.
+pub const SIDE: usize = 3;
+// Most boards offered in the menu.
+pub const MAX_BOARDS: usize = 3;
+// The mark both players place.
+pub const MARK: Player = Player::X;
+
+// Where a cell of board `index` is stored.
+pub fn board_cell(index: usize, row: usize, col: usize) -> (usize, usize) {
+    (row, index * SIDE + col)
+}
+
+// Which board a stored cell belongs to.
+pub fn board_of(col: usize) -> usize {
+    col / SIDE
+}
+
+// Every row, column and diagonal of each board.
+pub fn winning_lines(boards: usize) -> Vec<Line> {
+    let mut lines = Vec::new();
+    for index in 0..boards {
+        let mut add = |cells: Vec<(usize, usize)>| {
+            lines.push(Line {
+                kind: LineKind::Layer(index),
+                cells: cells
+                    .into_iter()
+                    .map(|(row, col)| board_cell(index, row, col))
+                    .collect(),
+            })
+        };
+        for i in 0..SIDE {
+            add((0..SIDE).map(|j| (i, j)).collect());
+            add((0..SIDE).map(|j| (j, i)).collect());
+        }
+        add((0..SIDE).map(|i| (i, i)).collect());
+        add((0..SIDE).map(|i| (i, SIDE - 1 - i)).collect());
+    }
+    lines
+}
+
+// `boards` empty boards side by side.
+pub fn new_board(boards: usize) -> Board {
+    Board::with_lines(SIDE, SIDE * boards, winning_lines(boards))
+}
+
+// How many boards are laid out side by side.
+pub fn board_count(board: &Board) -> usize {
+    let (_, cols) = board.dimensions();
+    cols / SIDE
+}
+
+// The marks of one board as bits, bit `3 * row + col` set for each marked cell.
+fn board_mask(board: &Board, index: usize) -> u16 {
+    let mut mask = 0;
+    for row in 0..SIDE {
+        for col in 0..SIDE {
+            let (r, c) = board_cell(index, row, col);
+            if board[r][c].is_some() {
+                mask |= 1 << (row * SIDE + col);
+            }
+        }
+    }
+    mask
+}
+
+const LINE_MASKS: [u16; 8] = [
+    0b000_000_111,
+    0b000_111_000,
+    0b111_000_000,
+    0b001_001_001,
+    0b010_010_010,
+    0b100_100_100,
+    0b100_010_001,
+    0b001_010_100,
+];
+
+fn is_dead_mask(mask: u16) -> bool {
+    LINE_MASKS.iter().any(|&line| line & !mask == 0)
+}
+
+// Whether board `index` has three in a row.
+pub fn is_dead(board: &Board, index: usize) -> bool {
+    is_dead_mask(board_mask(board, index))
+}
+
+// Whether every board is dead, which ends the game.
+pub fn all_dead(board: &Board) -> bool {
+    (0..board_count(board)).all(|index| is_dead(board, index))
+}
+
+// Whether a mark may go in the cell: it must be empty, on a board still alive.
+pub fn is_legal_move(board: &Board, (row, col): (usize, usize)) -> bool {
+    board[row][col].is_none() && !is_dead(board, board_of(col))
+}
+
+// The line that ended the game, once `last` killed the last board. Only that
+// line counts, and it is credited to the player who drew it.
+pub fn winning_line(board: &Board, last: &MoveRecord) -> Option<WinningLine> {
+    if !all_dead(board) {
+        return None;
+    }
+    let line = find_winning_line_through(board, last.row, last.col)?;
+    Some(WinningLine {
+        player: last.player,
+        ..line
+    })
+}
+
+// Empty cells on boards that are still alive.
+pub fn legal_moves(board: &Board) -> Vec<(usize, usize)> {
+    board
+        .empty_cells()
+        .filter(|&(_, col)| !is_dead(board, board_of(col)))
+        .collect()
+}
+
+// An element a^i b^j c^k d^l of the quotient monoid, with relations
+// a² = 1, b³ = b, b²c = c, c³ = ac², b²d = d, cd = ad and d² = c².
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quotient {
+    a: u8,
+    b: u8,
+    c: u8,
+    d: u8,
+}
+
+const ONE: Quotient = Quotient::new(0, 0, 0, 0);
+const A: Quotient = Quotient::new(1, 0, 0, 0);
+const B: Quotient = Quotient::new(0, 1, 0, 0);
+const AB: Quotient = Quotient::new(1, 1, 0, 0);
+const C: Quotient = Quotient::new(0, 0, 1, 0);
+const CC: Quotient = Quotient::new(0, 0, 2, 0);
+const D: Quotient = Quotient::new(0, 0, 0, 1);
+const AD: Quotient = Quotient::new(1, 0, 0, 1);
+
+// Values the player to move loses from: a, b², bc and c².
+const P_POSITIONS: [Quotient; 4] = [A, Quotient::new(0, 2, 0, 0), Quotient::new(0, 1, 1, 0), CC];
+
+impl Quotient {
+    const fn new(a: u8, b: u8, c: u8, d: u8) -> Self {
+        Quotient { a, b, c, d }
+    }
+
+    pub fn times(self, other: Quotient) -> Quotient {
+        Quotient {
+            a: self.a + other.a,
+            b: self.b + other.b,
+            c: self.c + other.c,
+            d: self.d + other.d,
+        }
+        .reduced()
+    }
+
+    // Applies the relations until the element is in normal form.
+    fn reduced(mut self) -> Quotient {
+        loop {
+            let before = self;
+            if self.d >= 2 {
+                // d² = c²
+                self.d -= 2;
+                self.c += 2;
+            }
+            if self.d >= 1 && self.c >= 1 {
+                // cd = ad
+                self.c -= 1;
+                self.a += 1;
+            }
+            if self.c >= 3 {
+                // c³ = ac²
+                self.c -= 1;
+                self.a += 1;
+            }
+            if (self.c >= 1 || self.d >= 1) && self.b >= 2 {
+                // b²c = c, b²d = d
+                self.b -= 2;
+            }
+            if self.b >= 3 {
+                // b³ = b
+                self.b -= 2;
+            }
+            // a² = 1
+            self.a %= 2;
+            if self == before {
+                return self;
+            }
+        }
+    }
+
+    // Whether the player to move loses from a position of this value.
+    pub fn is_p_position(self) -> bool {
+        P_POSITIONS.contains(&self)
+    }
+}
+
+// The value of every live board up to rotation and reflection, keyed by its
+// smallest mask under the eight symmetries (rows written bottom row first).
+const BOARD_VALUES: [(u16, Quotient); 46] = [
+    (0b000_000_000, C),
+    (0b000_000_001, ONE),
+    (0b000_000_010, ONE),
+    (0b000_000_011, AD),
+    (0b000_000_101, B),
+    (0b000_001_010, A),
+    (0b000_001_011, B),
+    (0b000_001_100, B),
+    (0b000_001_101, A),
+    (0b000_001_110, D),
+    (0b000_010_000, CC),
+    (0b000_010_001, B),
+    (0b000_010_010, B),
+    (0b000_010_011, AB),
+    (0b000_010_101, A),
+    (0b000_011_010, AB),
+    (0b000_011_011, A),
+    (0b000_011_100, A),
+    (0b000_011_101, B),
+    (0b000_011_110, B),
+    (0b000_101_000, A),
+    (0b000_101_001, D),
+    (0b000_101_010, B),
+    (0b000_101_011, A),
+    (0b000_101_101, B),
+    (0b001_000_100, A),
+    (0b001_000_101, AB),
+    (0b001_000_110, D),
+    (0b001_001_110, AB),
+    (0b001_100_001, A),
+    (0b001_100_010, ONE),
+    (0b001_100_011, B),
+    (0b001_100_101, B),
+    (0b001_100_110, A),
+    (0b001_101_010, AB),
+    (0b001_101_100, A),
+    (0b001_101_110, B),
+    (0b001_110_001, B),
+    (0b001_110_010, B),
+    (0b001_110_011, A),
+    (0b010_101_010, A),
+    (0b010_101_011, B),
+    (0b010_101_101, A),
+    (0b011_100_101, A),
+    (0b011_101_110, A),
+    (0b101_000_101, A),
+];
+
+// Maps `mask` through one of the eight symmetries of the board: a quarter
+// turn `symmetry % 4` times, after a mirror image when `symmetry >= 4`.
+fn transform(mask: u16, symmetry: usize) -> u16 {
+    let mut transformed = 0;
+    for cell in 0..SIDE * SIDE {
+        if mask & (1 << cell) == 0 {
+            continue;
+        }
+        let (mut row, mut col) = (cell / SIDE, cell % SIDE);
+        if symmetry >= 4 {
+            col = SIDE - 1 - col;
+        }
+        for _ in 0..symmetry % 4 {
+            (row, col) = (col, SIDE - 1 - row);
+        }
+        transformed |= 1 << (row * SIDE + col);
+    }
+    transformed
+}
+
+fn board_value(mask: u16) -> Quotient {
+    // A dead board takes no part in the game
+    if is_dead_mask(mask) {
+        return ONE;
+    }
+    let canonical = (0..8)
+        .map(|symmetry| transform(mask, symmetry))
+        .min()
+        .unwrap_or(mask);
+    BOARD_VALUES
+        .iter()
+        .find(|&&(key, _)| key == canonical)
+        .map(|&(_, value)| value)
+        .expect("every live board has a value")
+}
+
+// The value of the whole game: the product of every board's value.
+pub fn position_value(board: &Board) -> Quotient {
+    (0..board_count(board))
+        .map(|index| board_value(board_mask(board, index)))
+        .fold(ONE, Quotient::times)
+}
+
+// A move that leaves the opponent in a P-position, if there is one. When the
+// position is lost anyway, a move that keeps the game going is chosen so the
+// opponent still has to find the win.
+pub fn get_best_move(board: &Board) -> Option<(usize, usize)> {
+    let moves = legal_moves(board);
+    let mut board_copy = board.clone();
+    let mut fallback = None;
+
+    for &(row, col) in moves.iter() {
+        board_copy[row][col] = Some(MARK);
+        let value = position_value(&board_copy);
+        let game_over = all_dead(&board_copy);
+        board_copy[row][col] = None;
+
+        if value.is_p_position() {
+            println!("🎯 Notakto AI: {:?} leaves {:?}", (row, col), value);
+            return Some((row, col));
+        }
+        if !game_over && fallback.is_none() {
+            fallback = Some((row, col));
+        }
+    }
+
+    println!("🏳️ Notakto AI has no winning move");
+    fallback.or_else(|| moves.first().copied())
+}
+
+// A Notakto game as the shared search sees it, used below hard: O maximizes,
+// both players place X, and anything out of depth counts as even.
+pub struct NotaktoGame {
+    search_board: SearchBoard,
+    to_move: Player,
+}
+
+impl NotaktoGame {
+    pub fn new(board: &Board, to_move: Player) -> Self {
+        NotaktoGame {
+            search_board: SearchBoard::new(board),
+            to_move,
+        }
+    }
+}
+
+impl Game for NotaktoGame {
+    type Move = (usize, usize);
+
+    const NAME: &'static str = "Notakto";
+
+    fn maximizing(&self) -> bool {
+        self.to_move == Player::O
+    }
+
+    fn legal_moves(&self) -> Vec<(usize, usize)> {
+        legal_moves(&self.search_board.board)
+    }
+
+    fn apply(&mut self, cell: (usize, usize)) {
+        self.search_board.place(cell, MARK);
+        self.to_move = self.to_move.opposite();
+    }
+
+    fn undo(&mut self, cell: (usize, usize)) {
+        self.search_board.take_back(cell);
+        self.to_move = self.to_move.opposite();
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        // The player to move wins once the last board has been killed
+        all_dead(&self.search_board.board).then_some(Outcome::Win(self.maximizing()))
+    }
+}
+
+// Picks the AI's move for the given difficulty: hard plays perfectly by the
+// quotient, the others search.
+pub fn choose_move<R: Rng + ?Sized>(
+    board: &Board,
+    player: Player,
+    difficulty: Difficulty,
+    rng: &mut R,
+) -> Option<(usize, usize)> {
+    match difficulty {
+        Difficulty::Easy | Difficulty::Medium => {
+            search::choose_move(NotaktoGame::new(board, player), difficulty, rng)
+        }
+        Difficulty::Hard => get_best_move(board),
+    }
+}
@@ -0,0 +1,156 @@
+use std::cmp::Reverse;
+
+use crate::config::{ORDER_AND_CHAOS_AI_BREADTH, ORDER_AND_CHAOS_HARD_AI_DEPTH};
+use crate::search::{Game, Outcome, SearchBoard};
+use crate::types::{find_winning_line, Board, BoardConfig, Line, Player, Role, WinningLine};
+
+// Order and Chaos on a 6×6 board: both players place either symbol. Order
+// wins with five in a row of one symbol, Chaos by filling the board without
+// one, so there are no draws. The player who moves first plays Order.
+
+pub const ORDER_AND_CHAOS_BOARD: BoardConfig = BoardConfig::new(6, 5);
+
+// A move: the cell and the symbol placed there, as in wild games.
+pub type SymbolMove = ((usize, usize), Player);
+
+// Marks of each symbol on a line.
+fn symbol_counts(board: &Board, line: &Line) -> (u32, u32) {
+    let mut x_count = 0;
+    let mut o_count = 0;
+    for &(row, col) in line.cells.iter() {
+        match board[row][col] {
+            Some(Player::X) => x_count += 1,
+            Some(Player::O) => o_count += 1,
+            Some(Player::Triangle) => unreachable!("Order and Chaos only uses X and O"),
+            None => {}
+        }
+    }
+    (x_count, o_count)
+}
+
+// Scores the board from Order's point of view: every line still open to one
+// symbol counts, for more the fuller it is. A line holding both symbols can
+// never be completed and counts for nothing.
+fn evaluate(board: &Board) -> i32 {
+    board
+        .lines()
+        .iter()
+        .map(|line| match symbol_counts(board, line) {
+            (0, 0) => 1,
+            (count, 0) | (0, count) => 4_i32.pow(count),
+            _ => 0,
+        })
+        .sum()
+}
+
+// How promising a move looks to the player making it. Order wants to lengthen
+// lines of the same symbol; Chaos wants to spoil the fullest open lines by
+// placing the other symbol in them.
+fn move_priority(board: &Board, role: Role, (row, col): (usize, usize), symbol: Player) -> i32 {
+    board
+        .lines_through(row, col)
+        .map(|line| {
+            let (x_count, o_count) = symbol_counts(board, line);
+            let (same, other) = match symbol {
+                Player::X => (x_count, o_count),
+                Player::O => (o_count, x_count),
+                Player::Triangle => unreachable!("Order and Chaos only uses X and O"),
+            };
+            match role {
+                Role::Order if other == 0 => 10_i32.pow(same),
+                Role::Chaos if same == 0 && other > 0 => 10_i32.pow(other),
+                Role::Order | Role::Chaos => 0,
+            }
+        })
+        .sum()
+}
+
+// Five of either symbol, credited to `order` whoever placed the last mark.
+pub fn winning_line(board: &Board, order: Player) -> Option<WinningLine> {
+    let line = find_winning_line(board)?;
+    Some(WinningLine {
+        player: order,
+        ..line
+    })
+}
+
+// Every empty cell with both symbols.
+pub fn legal_moves(board: &Board) -> Vec<SymbolMove> {
+    board
+        .empty_cells()
+        .flat_map(|cell| Player::ALL.map(|symbol| (cell, symbol)))
+        .collect()
+}
+
+// The most promising moves for `role`, best first. With two symbols for each
+// of up to 36 cells, only the first `ORDER_AND_CHAOS_AI_BREADTH` are searched.
+fn ordered_moves(board: &Board, role: Role) -> Vec<SymbolMove> {
+    let mut moves: Vec<_> = legal_moves(board)
+        .into_iter()
+        .map(|(cell, symbol)| (move_priority(board, role, cell, symbol), (cell, symbol)))
+        .collect();
+    moves.sort_by_key(|&(priority, _)| Reverse(priority));
+    moves
+        .into_iter()
+        .take(ORDER_AND_CHAOS_AI_BREADTH)
+        .map(|(_, symbol_move)| symbol_move)
+        .collect()
+}
+
+// An Order and Chaos game as the shared search sees it, with Order
+// maximizing.
+pub struct OrderChaosGame {
+    search_board: SearchBoard,
+    to_move: Role,
+}
+
+impl OrderChaosGame {
+    pub fn new(board: &Board, to_move: Role) -> Self {
+        OrderChaosGame {
+            search_board: SearchBoard::new(board),
+            to_move,
+        }
+    }
+}
+
+impl Game for OrderChaosGame {
+    type Move = SymbolMove;
+
+    const NAME: &'static str = "Order and Chaos";
+    const HARD_DEPTH: i32 = ORDER_AND_CHAOS_HARD_AI_DEPTH;
+
+    fn maximizing(&self) -> bool {
+        self.to_move == Role::Order
+    }
+
+    fn legal_moves(&self) -> Vec<SymbolMove> {
+        legal_moves(&self.search_board.board)
+    }
+
+    fn search_moves(&self) -> Vec<SymbolMove> {
+        ordered_moves(&self.search_board.board, self.to_move)
+    }
+
+    fn apply(&mut self, (cell, symbol): SymbolMove) {
+        self.search_board.place(cell, symbol);
+        self.to_move = self.to_move.other();
+    }
+
+    fn undo(&mut self, (cell, _): SymbolMove) {
+        self.search_board.take_back(cell);
+        self.to_move = self.to_move.other();
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        // Five in a row wins for Order whoever placed it; a full board
+        // without one wins for Chaos
+        if self.search_board.completed_line().is_some() {
+            return Some(Outcome::Win(true));
+        }
+        self.search_board.is_full().then_some(Outcome::Win(false))
+    }
+
+    fn evaluate(&self) -> i32 {
+        evaluate(&self.search_board.board)
+    }
+}
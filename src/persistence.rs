@@ -0,0 +1,165 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{
+    CurrentAIDifficulty, CurrentGameMode, CurrentRules, CurrentSetup, CurrentTheme, GameStats,
+    MatchHistory, SeatAssignment,
+};
+use crate::types::{rules_or_board, Difficulty, GameMode, GameRecord, GameRules, GameSetup, Theme};
+
+// Statistics, completed games and menu settings kept between sessions.
+// Missing fields (e.g. from an older save) fall back to the defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveData {
+    pub stats: GameStats,
+    pub game_mode: GameMode,
+    pub difficulty: Difficulty,
+    pub theme: Theme,
+    pub seats: SeatAssignment,
+    // Older saves kept only the board, under `board`.
+    #[serde(alias = "board", deserialize_with = "rules_or_board")]
+    pub rules: GameRules,
+    pub setup: GameSetup,
+    pub games: Vec<GameRecord>,
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        Self {
+            stats: GameStats::default(),
+            game_mode: GameMode::HumanVsAI,
+            difficulty: Difficulty::Hard,
+            theme: Theme::default(),
+            seats: SeatAssignment::default(),
+            rules: GameRules::default(),
+            setup: GameSetup::default(),
+            games: Vec::new(),
+        }
+    }
+}
+
+impl SaveData {
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+// Startup system: inserts the saved stats and settings (or the defaults).
+pub fn load_saved_data(mut commands: Commands) {
+    let data = match storage::read() {
+        Some(json) => SaveData::from_json(&json).unwrap_or_else(|err| {
+            warn!("⚠️ Ignoring unreadable save data: {}", err);
+            SaveData::default()
+        }),
+        None => {
+            info!("💾 No save data found, using defaults");
+            SaveData::default()
+        }
+    };
+
+    info!("💾 Loaded {:?}", data);
+    commands.insert_resource(data.stats);
+    commands.insert_resource(CurrentGameMode(data.game_mode));
+    commands.insert_resource(CurrentAIDifficulty(data.difficulty));
+    commands.insert_resource(CurrentTheme(data.theme));
+    commands.insert_resource(data.seats);
+    commands.insert_resource(CurrentRules(data.rules));
+    commands.insert_resource(CurrentSetup(data.setup));
+    commands.insert_resource(MatchHistory(data.games));
+}
+
+// Writes the save whenever the stats, history or any setting change.
+#[allow(clippy::too_many_arguments)]
+pub fn save_on_change(
+    game_stats: Res<GameStats>,
+    match_history: Res<MatchHistory>,
+    game_mode: Res<CurrentGameMode>,
+    ai_difficulty: Res<CurrentAIDifficulty>,
+    theme: Res<CurrentTheme>,
+    seats: Res<SeatAssignment>,
+    rules: Res<CurrentRules>,
+    setup: Res<CurrentSetup>,
+) {
+    let changed = game_stats.is_changed()
+        || match_history.is_changed()
+        || game_mode.is_changed()
+        || ai_difficulty.is_changed()
+        || theme.is_changed()
+        || seats.is_changed()
+        || rules.is_changed()
+        || setup.is_changed();
+    if !changed {
+        return;
+    }
+
+    let data = SaveData {
+        stats: game_stats.clone(),
+        game_mode: game_mode.0,
+        difficulty: ai_difficulty.0,
+        theme: theme.0,
+        seats: *seats,
+        rules: rules.0,
+        setup: setup.0.clone(),
+        games: match_history.0.clone(),
+    };
+
+    match data.to_json() {
+        Ok(json) => match storage::write(&json) {
+            Ok(()) => info!("💾 Saved stats and settings"),
+            Err(err) => warn!("⚠️ Could not save: {}", err),
+        },
+        Err(err) => warn!("⚠️ Could not serialize save data: {}", err),
+    }
+}
+
+// Desktop: a JSON file in the platform config directory.
+#[cfg(not(target_arch = "wasm32"))]
+mod storage {
+    use std::fs;
+    use std::path::PathBuf;
+
+    const APP_DIR: &str = "bevy-tic-tac-toe";
+    const SAVE_FILE: &str = "save.json";
+
+    fn save_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join(APP_DIR).join(SAVE_FILE))
+    }
+
+    pub fn read() -> Option<String> {
+        fs::read_to_string(save_path()?).ok()
+    }
+
+    pub fn write(contents: &str) -> Result<(), String> {
+        let path = save_path().ok_or("no config directory on this platform")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+        fs::write(&path, contents).map_err(|err| err.to_string())
+    }
+}
+
+// Web: the browser's localStorage.
+#[cfg(target_arch = "wasm32")]
+mod storage {
+    const STORAGE_KEY: &str = "bevy-tic-tac-toe-save";
+
+    fn local_storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok().flatten()
+    }
+
+    pub fn read() -> Option<String> {
+        local_storage()?.get_item(STORAGE_KEY).ok().flatten()
+    }
+
+    pub fn write(contents: &str) -> Result<(), String> {
+        local_storage()
+            .ok_or("localStorage is not available")?
+            .set_item(STORAGE_KEY, contents)
+            .map_err(|err| format!("{:?}", err))
+    }
+}
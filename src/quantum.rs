@@ -0,0 +1,383 @@
+use std::collections::VecDeque;
+use std::sync::OnceLock;
+
+use crate::ai_logic::evaluate_board;
+use crate::config::QUANTUM_HARD_AI_DEPTH;
+use crate::search::{Game, Outcome};
+use crate::types::{Board, BoardConfig, MoveKind, MoveRecord, Player, WinningLine};
+
+// Quantum tic-tac-toe (Allan Goff's rules) on the classic board. Each move puts
+// a "spooky" mark, subscripted with the move number, in two cells at once; the
+// two cells are then entangled. When a move closes a cycle of entanglement the
+// other player collapses it by choosing which of its two cells the new mark
+// takes, and every mark entangled with it is forced into one cell in turn.
+// Only collapsed (classical) marks make lines. If a collapse gives both players
+// a line, the one whose line has the lower highest subscript wins (scoring a
+// full point to the other's half). With one cell left the last move is placed
+// classically there.
+//
+// `BoardState` keeps the classical marks on its `Board`, next to the rest of
+// the quantum state. A spooky move is recorded as `MoveKind::Quantum` with its
+// second cell; a collapse or a classical move as `MoveKind::Place`.
+
+pub const SIDE: usize = 3;
+
+// A mark made on move `turn` (counting from 1), still spread over two cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpookyMark {
+    pub player: Player,
+    pub turn: usize,
+    pub cells: [(usize, usize); 2],
+}
+
+impl SpookyMark {
+    fn other_cell(&self, cell: (usize, usize)) -> (usize, usize) {
+        if self.cells[0] == cell {
+            self.cells[1]
+        } else {
+            self.cells[0]
+        }
+    }
+}
+
+// A move: a spooky mark on `cell` and `partner`, or with no partner the cell a
+// pending collapse sends the new mark to (or the last cell, placed classically).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuantumMove {
+    pub cell: (usize, usize),
+    pub partner: Option<(usize, usize)>,
+}
+
+impl QuantumMove {
+    // The move a record stands for.
+    pub fn recorded(record: &MoveRecord) -> Self {
+        let partner = match record.kind {
+            MoveKind::Quantum { partner } => Some(partner),
+            MoveKind::Place => None,
+            other => unreachable!("not a quantum move: {:?}", other),
+        };
+        QuantumMove {
+            cell: (record.row, record.col),
+            partner,
+        }
+    }
+}
+
+// A finished game's deciding line. `split` is set when the other player made a
+// line in the same collapse, so the winner scores 1 point to their ½.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuantumWin {
+    pub line: WinningLine,
+    pub split: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct QuantumState {
+    // Collapsed marks and the move each was made on.
+    classical: [[Option<(Player, usize)>; SIDE]; SIDE],
+    // Marks still spread over two cells, oldest first.
+    spooky: Vec<SpookyMark>,
+    // The mark that closed a cycle, waiting for the player to move to collapse it.
+    pending: Option<SpookyMark>,
+    turn: usize,
+    to_move: Player,
+}
+
+// An empty classic board. The lines are built once and shared by every clone.
+fn empty_board() -> Board {
+    static EMPTY: OnceLock<Board> = OnceLock::new();
+    EMPTY
+        .get_or_init(|| Board::new(BoardConfig::CLASSIC))
+        .clone()
+}
+
+impl QuantumState {
+    pub fn new(first_player: Player) -> Self {
+        QuantumState {
+            classical: [[None; SIDE]; SIDE],
+            spooky: Vec::new(),
+            pending: None,
+            turn: 0,
+            to_move: first_player,
+        }
+    }
+
+    // The position reached by `moves`, starting with `first_player` to move.
+    pub fn replay(first_player: Player, moves: &[MoveRecord]) -> Self {
+        let mut state = QuantumState::new(first_player);
+        for record in moves {
+            state.apply(QuantumMove::recorded(record));
+        }
+        state
+    }
+
+    pub fn to_move(&self) -> Player {
+        self.to_move
+    }
+
+    pub fn classical(&self, row: usize, col: usize) -> Option<(Player, usize)> {
+        self.classical[row][col]
+    }
+
+    pub fn spooky_marks(&self) -> &[SpookyMark] {
+        &self.spooky
+    }
+
+    // The mark the player to move must collapse before anything else.
+    pub fn pending_collapse(&self) -> Option<SpookyMark> {
+        self.pending
+    }
+
+    // Cells without a classical mark.
+    pub fn free_cells(&self) -> Vec<(usize, usize)> {
+        (0..SIDE)
+            .flat_map(|row| (0..SIDE).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.classical[row][col].is_none())
+            .collect()
+    }
+
+    // Whether the next move is a spooky mark, placed by picking two cells.
+    pub fn needs_pair(&self) -> bool {
+        self.pending.is_none() && self.free_cells().len() > 1
+    }
+
+    pub fn is_legal(&self, quantum_move: QuantumMove) -> bool {
+        let free = |(row, col): (usize, usize)| self.classical[row][col].is_none();
+        if let Some(mark) = self.pending {
+            return quantum_move.partner.is_none() && mark.cells.contains(&quantum_move.cell);
+        }
+        match quantum_move.partner {
+            Some(partner) => {
+                self.needs_pair()
+                    && partner != quantum_move.cell
+                    && free(partner)
+                    && free(quantum_move.cell)
+            }
+            None => self.free_cells() == [quantum_move.cell],
+        }
+    }
+
+    pub fn legal_moves(&self) -> Vec<QuantumMove> {
+        if let Some(mark) = self.pending {
+            return mark
+                .cells
+                .iter()
+                .map(|&cell| QuantumMove {
+                    cell,
+                    partner: None,
+                })
+                .collect();
+        }
+        let free = self.free_cells();
+        if free.len() == 1 {
+            return vec![QuantumMove {
+                cell: free[0],
+                partner: None,
+            }];
+        }
+        let mut moves = Vec::new();
+        for (i, &partner) in free.iter().enumerate() {
+            for &cell in &free[i + 1..] {
+                moves.push(QuantumMove {
+                    cell,
+                    partner: Some(partner),
+                });
+            }
+        }
+        moves
+    }
+
+    // Plays a move that `is_legal`. Collapsing does not pass the turn: the
+    // player who collapses then makes their own move.
+    pub fn apply(&mut self, quantum_move: QuantumMove) {
+        if let Some(mark) = self.pending.take() {
+            self.collapse(mark, quantum_move.cell);
+            return;
+        }
+
+        self.turn += 1;
+        let (row, col) = quantum_move.cell;
+        match quantum_move.partner {
+            Some(partner) => {
+                let cycle = self.entangled(partner, quantum_move.cell);
+                let mark = SpookyMark {
+                    player: self.to_move,
+                    turn: self.turn,
+                    cells: [partner, quantum_move.cell],
+                };
+                self.spooky.push(mark);
+                if cycle {
+                    self.pending = Some(mark);
+                }
+            }
+            None => self.classical[row][col] = Some((self.to_move, self.turn)),
+        }
+        self.to_move = self.to_move.opposite();
+    }
+
+    // Whether a chain of spooky marks already links the two cells, so a mark
+    // between them would close a cycle.
+    fn entangled(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        let mut seen = vec![from];
+        let mut queue = VecDeque::from([from]);
+        while let Some(cell) = queue.pop_front() {
+            if cell == to {
+                return true;
+            }
+            for mark in self.spooky.iter().filter(|mark| mark.cells.contains(&cell)) {
+                let next = mark.other_cell(cell);
+                if !seen.contains(&next) {
+                    seen.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+        false
+    }
+
+    // Sends `mark` to `cell`; any other spooky mark in that cell is pushed out
+    // to its other cell, and so on until the entangled group has collapsed.
+    fn collapse(&mut self, mark: SpookyMark, cell: (usize, usize)) {
+        let mut forced = vec![(mark, cell)];
+        self.spooky.retain(|&other| other != mark);
+        while let Some((mark, (row, col))) = forced.pop() {
+            self.classical[row][col] = Some((mark.player, mark.turn));
+            let (pushed_out, rest): (Vec<_>, Vec<_>) = self
+                .spooky
+                .iter()
+                .partition(|other| other.cells.contains(&(row, col)));
+            self.spooky = rest;
+            forced.extend(
+                pushed_out
+                    .into_iter()
+                    .map(|other| (other, other.other_cell((row, col)))),
+            );
+        }
+    }
+
+    // The classical marks as a board.
+    pub fn board(&self) -> Board {
+        let mut board = empty_board();
+        for row in 0..SIDE {
+            for col in 0..SIDE {
+                board[row][col] = self.classical[row][col].map(|(player, _)| player);
+            }
+        }
+        board
+    }
+
+    // The deciding line once classical marks make one: if both players have
+    // lines, the one whose highest subscript is lower.
+    pub fn result(&self) -> Option<QuantumWin> {
+        let board = self.board();
+        let mut lines: Vec<(usize, WinningLine)> = board
+            .lines()
+            .iter()
+            .filter_map(|line| {
+                let (row, col) = line.cells[0];
+                let (player, _) = self.classical[row][col]?;
+                let mut latest = 0;
+                for &(row, col) in line.cells.iter() {
+                    match self.classical[row][col] {
+                        Some((owner, turn)) if owner == player => latest = latest.max(turn),
+                        _ => return None,
+                    }
+                }
+                Some((
+                    latest,
+                    WinningLine {
+                        player,
+                        kind: line.kind,
+                        cells: line.cells.clone(),
+                    },
+                ))
+            })
+            .collect();
+        lines.sort_by_key(|&(latest, _)| latest);
+
+        let (_, line) = lines.first()?.clone();
+        let split = lines.iter().any(|(_, other)| other.player != line.player);
+        Some(QuantumWin { line, split })
+    }
+
+    // Whether the game has ended with every cell classical and no line.
+    pub fn is_draw(&self) -> bool {
+        self.free_cells().is_empty() && self.result().is_none()
+    }
+}
+
+// Written as subscript digits, e.g. 12 as "₁₂".
+pub fn subscript(turn: usize) -> String {
+    turn.to_string()
+        .chars()
+        .filter_map(|digit| digit.to_digit(10))
+        .filter_map(|digit| char::from_u32(0x2080 + digit))
+        .collect()
+}
+
+// A quantum game as the shared search sees it, with O maximizing. Collapsing
+// does not pass the turn, so who maximizes comes from the position rather
+// than alternating, and a collapse is a ply of its own. Out of depth only the
+// classical marks are scored.
+pub struct QuantumGame {
+    state: QuantumState,
+    // The positions before each move played, to take them back.
+    previous: Vec<QuantumState>,
+    misere: bool,
+}
+
+impl QuantumGame {
+    pub fn new(state: &QuantumState, misere: bool) -> Self {
+        QuantumGame {
+            state: state.clone(),
+            previous: Vec::new(),
+            misere,
+        }
+    }
+}
+
+impl Game for QuantumGame {
+    type Move = QuantumMove;
+
+    const NAME: &'static str = "Quantum";
+    const HARD_DEPTH: i32 = QUANTUM_HARD_AI_DEPTH;
+
+    fn maximizing(&self) -> bool {
+        self.state.to_move() == Player::O
+    }
+
+    fn legal_moves(&self) -> Vec<QuantumMove> {
+        self.state.legal_moves()
+    }
+
+    fn apply(&mut self, quantum_move: QuantumMove) {
+        self.previous.push(self.state.clone());
+        self.state.apply(quantum_move);
+    }
+
+    fn undo(&mut self, _: QuantumMove) {
+        if let Some(previous) = self.previous.pop() {
+            self.state = previous;
+        }
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        if let Some(win) = self.state.result() {
+            let maximizer = win.line.player == Player::O;
+            return Some(if win.split {
+                Outcome::SplitWin(maximizer)
+            } else {
+                Outcome::Win(maximizer)
+            });
+        }
+        self.state.is_draw().then_some(Outcome::Draw)
+    }
+
+    fn evaluate(&self) -> i32 {
+        evaluate_board(&self.state.board())
+    }
+
+    fn misere(&self) -> bool {
+        self.misere
+    }
+}
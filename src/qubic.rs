@@ -0,0 +1,184 @@
+use std::cmp::Reverse;
+use std::sync::OnceLock;
+
+use crate::ai_logic::evaluate_board;
+use crate::config::{QUBIC_AI_BREADTH, QUBIC_HARD_AI_DEPTH};
+use crate::search::{Game, Outcome, SearchBoard};
+use crate::types::{Board, Line, LineKind, Player};
+
+// Qubic: four in a row on a 4×4×4 cube. The cube is stored as one 4×16 `Board`
+// with its layers side by side, so cell (row, col) of layer `l` lives at
+// (row, 4 * l + col). Lines run along rows, columns and diagonals inside a
+// layer, or through all four layers, 76 in total.
+
+// Cells along each edge of the cube.
+pub const SIDE: usize = 4;
+pub const LAYERS: usize = SIDE;
+
+// Where a cell of the cube is stored on the 4×16 board.
+pub fn board_cell(layer: usize, row: usize, col: usize) -> (usize, usize) {
+    (row, layer * SIDE + col)
+}
+
+// Every line of four cells through the cube. Each line is found once, from
+// the end where stepping back one more cell would leave the cube.
+pub fn winning_lines() -> Vec<Line> {
+    let in_cube = |value: isize| (0..SIDE as isize).contains(&value);
+    let mut lines = Vec::new();
+
+    for dl in -1..=1_isize {
+        for dr in -1..=1_isize {
+            for dc in -1..=1_isize {
+                // One direction of each pair: the first non-zero step is positive
+                let first_step = [dl, dr, dc].into_iter().find(|&step| step != 0);
+                if first_step != Some(1) {
+                    continue;
+                }
+
+                for layer in 0..SIDE as isize {
+                    for row in 0..SIDE as isize {
+                        for col in 0..SIDE as isize {
+                            let before = (layer - dl, row - dr, col - dc);
+                            if in_cube(before.0) && in_cube(before.1) && in_cube(before.2) {
+                                continue;
+                            }
+                            let last = SIDE as isize - 1;
+                            let end = (layer + last * dl, row + last * dr, col + last * dc);
+                            if !(in_cube(end.0) && in_cube(end.1) && in_cube(end.2)) {
+                                continue;
+                            }
+
+                            let cells = (0..SIDE as isize)
+                                .map(|i| {
+                                    board_cell(
+                                        (layer + i * dl) as usize,
+                                        (row + i * dr) as usize,
+                                        (col + i * dc) as usize,
+                                    )
+                                })
+                                .collect();
+                            let kind = if dl == 0 {
+                                LineKind::Layer(layer as usize)
+                            } else {
+                                LineKind::CrossLayer
+                            };
+                            lines.push(Line { kind, cells });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    lines
+}
+
+// An empty Qubic board. The lines are built once and shared by every clone.
+pub fn new_board() -> Board {
+    static EMPTY: OnceLock<Board> = OnceLock::new();
+    EMPTY
+        .get_or_init(|| Board::with_lines(SIDE, SIDE * LAYERS, winning_lines()))
+        .clone()
+}
+
+// How promising a cell looks before searching it: lines through it holding
+// marks of only one player count for more the fuller they are, so cells that
+// win or block a win come first.
+fn move_priority(board: &Board, row: usize, col: usize) -> i32 {
+    board
+        .lines_through(row, col)
+        .map(|line| {
+            let mut x_count = 0;
+            let mut o_count = 0;
+            for &(r, c) in line.cells.iter() {
+                match board[r][c] {
+                    Some(Player::X | Player::Triangle) => x_count += 1,
+                    Some(Player::O) => o_count += 1,
+                    None => {}
+                }
+            }
+            match (x_count, o_count) {
+                (count, 0) | (0, count) => 10_i32.pow(count),
+                _ => 0,
+            }
+        })
+        .sum()
+}
+
+// The most promising empty cells, best first. Searching every one of up to 64
+// moves at each ply is out of reach, so only the first `QUBIC_AI_BREADTH` are
+// kept.
+fn ordered_moves(board: &Board) -> Vec<(usize, usize)> {
+    let mut moves: Vec<_> = board
+        .empty_cells()
+        .map(|(row, col)| (move_priority(board, row, col), (row, col)))
+        .collect();
+    moves.sort_by_key(|&(priority, _)| Reverse(priority));
+    moves
+        .into_iter()
+        .take(QUBIC_AI_BREADTH)
+        .map(|(_, cell)| cell)
+        .collect()
+}
+
+// A Qubic game as the shared search sees it: O maximizes, only the most
+// promising moves are searched, and unfinished positions are scored with
+// `evaluate_board`.
+pub struct QubicGame {
+    search_board: SearchBoard,
+    to_move: Player,
+    misere: bool,
+}
+
+impl QubicGame {
+    pub fn new(board: &Board, to_move: Player, misere: bool) -> Self {
+        QubicGame {
+            search_board: SearchBoard::new(board),
+            to_move,
+            misere,
+        }
+    }
+}
+
+impl Game for QubicGame {
+    type Move = (usize, usize);
+
+    const NAME: &'static str = "Qubic";
+    const HARD_DEPTH: i32 = QUBIC_HARD_AI_DEPTH;
+
+    fn maximizing(&self) -> bool {
+        self.to_move == Player::O
+    }
+
+    fn legal_moves(&self) -> Vec<(usize, usize)> {
+        self.search_board.board.empty_cells().collect()
+    }
+
+    fn search_moves(&self) -> Vec<(usize, usize)> {
+        ordered_moves(&self.search_board.board)
+    }
+
+    fn apply(&mut self, cell: (usize, usize)) {
+        self.search_board.place(cell, self.to_move);
+        self.to_move = self.to_move.opposite();
+    }
+
+    fn undo(&mut self, cell: (usize, usize)) {
+        self.search_board.take_back(cell);
+        self.to_move = self.to_move.opposite();
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        if let Some(line) = self.search_board.completed_line() {
+            return Some(Outcome::Win(line.player == Player::O));
+        }
+        self.search_board.is_full().then_some(Outcome::Draw)
+    }
+
+    fn evaluate(&self) -> i32 {
+        evaluate_board(&self.search_board.board)
+    }
+
+    fn misere(&self) -> bool {
+        self.misere
+    }
+}
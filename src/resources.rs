@@ -1,22 +1,600 @@
-use crate::types::{Board, Difficulty, GameMode, Player};
+use crate::config::{
+    AI_MOVE_DELAY, CELL_SIZE, LAYERED_BOARD_CENTER_X, LAYERED_BOARD_PIXELS, LAYER_GAP_RATIO,
+    MAX_BOARD_PIXELS,
+};
+use crate::gobblet::{GobbletMove, GobbletState, PieceSize};
+use crate::quantum::{QuantumMove, QuantumState};
+use crate::types::{
+    find_winning_line, is_board_full, Board, BoardConfig, Difficulty, GameMode, GameRecord,
+    GameResult, GameRules, GameSetup, GameVariant, MoveKind, MoveRecord, Player, Role,
+    StartingPosition, Theme, WinningLine,
+};
+use crate::{gobblet, gravity, notakto, order_chaos, qubic, three_marks, ultimate, wild};
 use bevy::prelude::*;
 use bevy::time::TimerMode;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
 
 #[derive(Resource)]
 pub struct BoardState {
+    pub rules: GameRules,
     pub board: Board,
+    // Blocked cells and handicap marks the game started with.
+    pub start: StartingPosition,
     pub current_player: Player,
+    pub starting_player: Player,
     pub game_over: bool,
     pub winner: Option<Player>,
+    // Moves played so far, oldest first.
+    pub moves: Vec<MoveRecord>,
+    // Undone moves, most recently undone last.
+    pub redo_stack: Vec<MoveRecord>,
+    // Spooky marks and pending collapses of a quantum game, kept up to date
+    // as moves are played.
+    quantum: QuantumState,
+    // Stacks and reserves of a Gobblet Gobblers game, likewise.
+    gobblet: GobbletState,
+}
+
+impl BoardState {
+    // An empty board for the given rules with `starting_player` to move.
+    pub fn new(rules: GameRules, starting_player: Player) -> Self {
+        Self {
+            rules,
+            board: rules.new_board(),
+            start: StartingPosition::default(),
+            current_player: starting_player,
+            starting_player,
+            game_over: false,
+            winner: None,
+            moves: Vec::new(),
+            redo_stack: Vec::new(),
+            quantum: QuantumState::new(starting_player),
+            gobblet: GobbletState::new(starting_player),
+        }
+    }
+
+    // Starts the game from `setup` on plain boards: its hand-picked cells and
+    // `random_blocked` more are blocked, leaving at least half the board open,
+    // then `handicap` marks go to `weaker` on the open cells that take part in
+    // the most lines.
+    pub fn apply_setup<R: Rng + ?Sized>(&mut self, setup: &GameSetup, weaker: Player, rng: &mut R) {
+        if !self.rules.variant.uses_board_preset() {
+            return;
+        }
+
+        let (rows, cols) = self.board.dimensions();
+        let mut blocked: Vec<_> = setup
+            .blocked
+            .iter()
+            .copied()
+            .filter(|&(row, col)| row < rows && col < cols)
+            .collect();
+        let random = setup
+            .random_blocked
+            .min((rows * cols / 2).saturating_sub(blocked.len()));
+        let open: Vec<_> = self
+            .board
+            .empty_cells()
+            .filter(|cell| !blocked.contains(cell))
+            .collect();
+        blocked.extend(open.choose_multiple(rng, random).copied());
+
+        self.start = StartingPosition {
+            blocked,
+            marks: Vec::new(),
+        };
+        self.board = self.start.board(self.rules);
+        for _ in 0..setup.handicap {
+            let Some((row, col)) = self.handicap_cell() else {
+                break;
+            };
+            self.board[row][col] = Some(weaker);
+            self.start.marks.push(((row, col), weaker));
+        }
+    }
+
+    // The open cell in the most lines, nearest the centre on ties. Under
+    // gravity only the cell a mark would land on counts in each column.
+    fn handicap_cell(&self) -> Option<(usize, usize)> {
+        let (rows, cols) = self.board.dimensions();
+        let centre_distance = |(row, col): (usize, usize)| {
+            (2 * row).abs_diff(rows - 1) + (2 * col).abs_diff(cols - 1)
+        };
+        self.board
+            .empty_cells()
+            .filter(|&(row, col)| self.landing_cell(row, col) == Some((row, col)))
+            .max_by_key(|&(row, col)| {
+                (
+                    self.board.lines_through(row, col).count(),
+                    Reverse(centre_distance((row, col))),
+                )
+            })
+    }
+
+    // Blocks an open cell or reopens a blocked one before the first move on
+    // plain boards. Returns whether the cell ends up blocked, or None if it
+    // cannot be toggled.
+    pub fn toggle_blocked(&mut self, row: usize, col: usize) -> Option<bool> {
+        if !self.moves.is_empty()
+            || !self.rules.variant.uses_board_preset()
+            || self.board[row][col].is_some()
+        {
+            return None;
+        }
+
+        let blocked = !self.board.is_blocked(row, col);
+        if blocked {
+            self.start.blocked.push((row, col));
+        } else {
+            self.start.blocked.retain(|&cell| cell != (row, col));
+        }
+        self.board = self.start.board(self.rules);
+        Some(blocked)
+    }
+
+    // The cell played last, which decides where Ultimate continues.
+    pub fn last_move(&self) -> Option<(usize, usize)> {
+        self.moves.last().map(|record| (record.row, record.col))
+    }
+
+    // The cell a move aimed at (row, col) ends up in. Under gravity the mark
+    // falls to the lowest empty cell of the column, and a full column has none.
+    pub fn landing_cell(&self, row: usize, col: usize) -> Option<(usize, usize)> {
+        match self.rules.variant {
+            GameVariant::Gravity => gravity::drop_row(&self.board, col).map(|row| (row, col)),
+            GameVariant::Standard
+            | GameVariant::Ultimate
+            | GameVariant::Qubic
+            | GameVariant::Disappearing
+            | GameVariant::Sliding
+            | GameVariant::Wild
+            | GameVariant::Notakto
+            | GameVariant::Quantum
+            | GameVariant::OrderAndChaos
+            | GameVariant::Gobblet
+            | GameVariant::ThreePlayer => Some((row, col)),
+        }
+    }
+
+    // Whether the player to move may make the move in the given cell.
+    pub fn is_legal(&self, (row, col): (usize, usize), kind: MoveKind) -> bool {
+        let variant = self.rules.variant;
+        let open = self.board[row][col].is_none() && !self.board.is_blocked(row, col);
+        match (variant, kind) {
+            (_, MoveKind::Swap) => {
+                self.can_swap() && (self.moves[0].row, self.moves[0].col) == (row, col)
+            }
+            (
+                GameVariant::Standard | GameVariant::Qubic | GameVariant::ThreePlayer,
+                MoveKind::Place,
+            )
+            | (GameVariant::Wild | GameVariant::OrderAndChaos, MoveKind::Wild { .. }) => open,
+            (GameVariant::Gravity, MoveKind::Place) => {
+                gravity::is_legal_move(&self.board, (row, col))
+            }
+            (GameVariant::Ultimate, MoveKind::Place) => {
+                ultimate::is_legal_move(&self.board, self.last_move(), row, col)
+            }
+            (GameVariant::Disappearing | GameVariant::Sliding, MoveKind::Place) => {
+                three_marks::is_legal_move(
+                    variant,
+                    &self.board,
+                    self.current_player,
+                    None,
+                    (row, col),
+                )
+            }
+            (GameVariant::Sliding, MoveKind::Slide { from }) => three_marks::is_legal_move(
+                variant,
+                &self.board,
+                self.current_player,
+                Some(from),
+                (row, col),
+            ),
+            (GameVariant::Notakto, MoveKind::Place) => {
+                notakto::is_legal_move(&self.board, (row, col))
+            }
+            (GameVariant::Quantum, MoveKind::Place) => self.quantum().is_legal(QuantumMove {
+                cell: (row, col),
+                partner: None,
+            }),
+            (GameVariant::Quantum, MoveKind::Quantum { partner }) => {
+                self.quantum().is_legal(QuantumMove {
+                    cell: (row, col),
+                    partner: Some(partner),
+                })
+            }
+            (GameVariant::Gobblet, MoveKind::Gobblet { size, from }) => {
+                self.gobblet().is_legal(GobbletMove {
+                    from,
+                    size,
+                    to: (row, col),
+                })
+            }
+            // Any other kind of move does not exist in this variant
+            _ => false,
+        }
+    }
+
+    // Whether the player to move has to move one of their marks rather than
+    // place a new one.
+    pub fn must_move_mark(&self) -> bool {
+        three_marks::must_move_mark(self.rules.variant, &self.board, self.current_player)
+    }
+
+    // Whether a click on this cell picks it up as `SelectedMark`: one of the
+    // player's own marks when one must be moved, the first cell of a spooky
+    // mark in quantum games, or one of the player's pieces on top of a stack
+    // in Gobblet Gobblers.
+    pub fn is_selectable(&self, row: usize, col: usize) -> bool {
+        match self.rules.variant {
+            GameVariant::Sliding => {
+                self.must_move_mark() && self.board[row][col] == Some(self.current_player)
+            }
+            GameVariant::Quantum => self.quantum().needs_pair() && self.board[row][col].is_none(),
+            GameVariant::Gobblet => self.board[row][col] == Some(self.current_player),
+            GameVariant::Standard
+            | GameVariant::Ultimate
+            | GameVariant::Qubic
+            | GameVariant::Gravity
+            | GameVariant::Disappearing
+            | GameVariant::Wild
+            | GameVariant::Notakto
+            | GameVariant::OrderAndChaos
+            | GameVariant::ThreePlayer => false,
+        }
+    }
+
+    // Stacks and reserves of a Gobblet Gobblers game.
+    pub fn gobblet(&self) -> &GobbletState {
+        &self.gobblet
+    }
+
+    // The side `player` plays in Order and Chaos, where the first player is Order.
+    pub fn role_of(&self, player: Player) -> Option<Role> {
+        (self.rules.variant == GameVariant::OrderAndChaos).then(|| {
+            if player == self.starting_player {
+                Role::Order
+            } else {
+                Role::Chaos
+            }
+        })
+    }
+
+    // The winner of a game that ended without a line: Chaos once the board
+    // fills up in Order and Chaos.
+    pub fn winner_without_line(&self) -> Option<Player> {
+        let chaos_won = self.rules.variant == GameVariant::OrderAndChaos
+            && self.winning_line().is_none()
+            && is_board_full(&self.board);
+        chaos_won.then(|| self.starting_player.opposite())
+    }
+
+    // Spooky marks and pending collapses of a quantum game.
+    pub fn quantum(&self) -> &QuantumState {
+        &self.quantum
+    }
+
+    // The completed line, if the game has been won.
+    pub fn winning_line(&self) -> Option<WinningLine> {
+        match self.rules.variant {
+            GameVariant::Standard
+            | GameVariant::Qubic
+            | GameVariant::Gravity
+            | GameVariant::Disappearing
+            | GameVariant::Sliding
+            | GameVariant::ThreePlayer => find_winning_line(&self.board),
+            GameVariant::Ultimate => ultimate::find_winning_line(&self.board),
+            GameVariant::Wild => wild::winning_line(&self.board, self.moves.last()?.player),
+            GameVariant::Notakto => notakto::winning_line(&self.board, self.moves.last()?),
+            GameVariant::Quantum => self.quantum().result().map(|win| win.line),
+            GameVariant::Gobblet => self.gobblet().winning_line(),
+            // The first player plays Order
+            GameVariant::OrderAndChaos => {
+                order_chaos::winning_line(&self.board, self.starting_player)
+            }
+        }
+    }
+
+    // Whether a quantum game was won 1–½, the loser having made a line too.
+    pub fn is_split_win(&self) -> bool {
+        self.rules.variant == GameVariant::Quantum
+            && self.quantum().result().is_some_and(|win| win.split)
+    }
+
+    // Whether the game has ended without a winner.
+    pub fn is_draw(&self) -> bool {
+        match self.rules.variant {
+            GameVariant::Standard
+            | GameVariant::Qubic
+            | GameVariant::Wild
+            | GameVariant::ThreePlayer => {
+                self.winning_line().is_none() && is_board_full(&self.board)
+            }
+            // Cells under a block stay empty, so no column having room ends it
+            GameVariant::Gravity => {
+                self.winning_line().is_none() && gravity::legal_moves(&self.board).is_empty()
+            }
+            GameVariant::Ultimate => {
+                ultimate::game_result(&self.board, self.last_move()) == GameResult::Draw
+            }
+            GameVariant::Disappearing | GameVariant::Sliding => {
+                three_marks::is_draw(self.rules.variant, &self.board, &self.moves)
+            }
+            // The last board always dies eventually
+            GameVariant::Notakto => false,
+            GameVariant::Quantum => self.quantum().is_draw(),
+            // A full board is a win for Chaos
+            GameVariant::OrderAndChaos => false,
+            GameVariant::Gobblet => {
+                gobblet::is_draw(self.gobblet(), self.starting_player, &self.moves)
+            }
+        }
+    }
+
+    // Makes the move for the player to move in the given cell, records it and
+    // passes the turn; a new move discards anything that could have been
+    // redone. Under disappearing rules the player's oldest mark is removed as
+    // needed, and Notakto marks are always X.
+    pub fn play(&mut self, (row, col): (usize, usize), kind: MoveKind) -> MoveRecord {
+        let kind = match (self.rules.variant, kind) {
+            (GameVariant::Disappearing, MoveKind::Place) => {
+                three_marks::disappearing_mark(self.rules.variant, &self.moves, self.current_player)
+                    .map_or(MoveKind::Place, |from| MoveKind::Slide { from })
+            }
+            (GameVariant::Notakto, MoveKind::Place) => MoveKind::Wild {
+                symbol: notakto::MARK,
+            },
+            _ => kind,
+        };
+        let record = self.place(MoveRecord {
+            row,
+            col,
+            player: self.current_player,
+            kind,
+        });
+        self.redo_stack.clear();
+        record
+    }
+
+    // Whether the player to move may take over the opening move under the
+    // swap rule instead of replying to it.
+    pub fn can_swap(&self) -> bool {
+        self.rules.swap_allowed() && self.moves.len() == 1 && !self.game_over
+    }
+
+    fn place(&mut self, record: MoveRecord) -> MoveRecord {
+        // Quantum marks only reach the board when they collapse, and
+        // collapsing does not pass the turn
+        if self.rules.variant == GameVariant::Quantum {
+            let collapsing = self.quantum.pending_collapse().is_some();
+            self.quantum.apply(QuantumMove::recorded(&record));
+            self.moves.push(record);
+            self.board = self.quantum.board();
+            self.current_player = if collapsing {
+                record.player
+            } else {
+                self.rules.next_player(record.player)
+            };
+            return record;
+        }
+        // Gobblet pieces may cover others, so the board shows the top pieces
+        if self.rules.variant == GameVariant::Gobblet {
+            self.gobblet.apply(GobbletMove::recorded(&record));
+            self.moves.push(record);
+            self.board = self.gobblet.board();
+            self.current_player = self.rules.next_player(record.player);
+            return record;
+        }
+
+        if let Some((from_row, from_col)) = record.vacated() {
+            self.board[from_row][from_col] = None;
+        }
+        self.board[record.row][record.col] = Some(record.mark());
+        self.moves.push(record);
+        self.current_player = self.rules.next_player(record.player);
+        record
+    }
+
+    // Takes back the last move; the player who made it is to move again.
+    pub fn undo(&mut self) -> Option<MoveRecord> {
+        let record = self.moves.pop()?;
+        if self.rules.variant == GameVariant::Quantum {
+            // A collapse cannot be taken back in place, so the rest is replayed
+            self.quantum = QuantumState::replay(self.starting_player, &self.moves);
+            self.board = self.quantum.board();
+        } else if self.rules.variant == GameVariant::Gobblet {
+            self.gobblet.undo(GobbletMove::recorded(&record));
+            self.board = self.gobblet.board();
+        } else if record.kind == MoveKind::Swap {
+            // The opening mark goes back to the opener
+            self.board[record.row][record.col] = self.moves.last().map(MoveRecord::mark);
+        } else {
+            self.board[record.row][record.col] = None;
+            if let Some((from_row, from_col)) = record.vacated() {
+                self.board[from_row][from_col] = Some(record.player);
+            }
+        }
+        self.current_player = record.player;
+        self.game_over = false;
+        self.winner = None;
+        self.redo_stack.push(record);
+        Some(record)
+    }
+
+    // Replays the most recently undone move.
+    pub fn redo(&mut self) -> Option<MoveRecord> {
+        let record = self.redo_stack.pop()?;
+        Some(self.place(record))
+    }
+
+    // Undoes moves until a human is to move again, so against the AI both its
+    // reply and the human move are taken back. Does nothing if no human move
+    // can be reached.
+    pub fn undo_turn(&mut self, is_ai_turn: impl Fn(Player) -> bool) -> Vec<MoveRecord> {
+        let Some(steps) = self
+            .moves
+            .iter()
+            .rposition(|record| !is_ai_turn(record.player))
+            .map(|index| self.moves.len() - index)
+        else {
+            return Vec::new();
+        };
+
+        (0..steps).filter_map(|_| self.undo()).collect()
+    }
+
+    // Redoes moves until a human is to move again (or nothing is left to redo).
+    pub fn redo_turn(&mut self, is_ai_turn: impl Fn(Player) -> bool) -> Vec<MoveRecord> {
+        let mut redone = Vec::new();
+        while let Some(record) = self.redo() {
+            redone.push(record);
+            if !is_ai_turn(self.current_player) {
+                break;
+            }
+        }
+        redone
+    }
 }
 
 #[derive(Resource)]
-#[allow(dead_code)]
 pub struct CurrentGameMode(pub GameMode);
 
 #[derive(Resource)]
 pub struct CurrentAIDifficulty(pub Difficulty);
 
+#[derive(Resource)]
+pub struct CurrentTheme(pub Theme);
+
+// Variant and board size used for new games.
+#[derive(Resource, Default)]
+pub struct CurrentRules(pub GameRules);
+
+// Blocked cells and handicap applied to each new game.
+#[derive(Resource, Default)]
+pub struct CurrentSetup(pub GameSetup);
+
+// On-screen geometry of the board, set when the board is spawned.
+// Cells shrink on larger boards so the whole grid fits beside the scoreboard.
+// Layered boards (Qubic, Notakto) are drawn as several grids side by side.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct BoardLayout {
+    pub rows: usize,
+    pub cols: usize,
+    // Columns in each separately drawn grid; equal to `cols` for a single grid.
+    pub layer_cols: usize,
+    pub cell_size: f32,
+    // Centre of the whole board.
+    pub center: Vec2,
+}
+
+impl Default for BoardLayout {
+    fn default() -> Self {
+        Self::new(BoardConfig::default().size)
+    }
+}
+
+impl BoardLayout {
+    // A single square grid centred on the origin.
+    pub fn new(size: usize) -> Self {
+        Self {
+            rows: size,
+            cols: size,
+            layer_cols: size,
+            cell_size: (MAX_BOARD_PIXELS / size as f32).min(CELL_SIZE),
+            center: Vec2::ZERO,
+        }
+    }
+
+    // `layers` grids of `rows` x `layer_cols` cells side by side.
+    pub fn layered(rows: usize, layer_cols: usize, layers: usize) -> Self {
+        let width_in_cells = (layer_cols * layers) as f32 + (layers - 1) as f32 * LAYER_GAP_RATIO;
+        Self {
+            rows,
+            cols: layer_cols * layers,
+            layer_cols,
+            cell_size: (LAYERED_BOARD_PIXELS / width_in_cells).min(CELL_SIZE),
+            center: Vec2::new(LAYERED_BOARD_CENTER_X, 0.0),
+        }
+    }
+
+    pub fn for_rules(rules: GameRules) -> Self {
+        match rules.variant {
+            GameVariant::Standard
+            | GameVariant::Gravity
+            | GameVariant::Wild
+            | GameVariant::ThreePlayer => Self::new(rules.board.size),
+            GameVariant::Disappearing
+            | GameVariant::Sliding
+            | GameVariant::Quantum
+            | GameVariant::Gobblet => Self::new(BoardConfig::CLASSIC.size),
+            GameVariant::Ultimate => Self::new(ultimate::ULTIMATE_BOARD.size),
+            GameVariant::OrderAndChaos => Self::new(order_chaos::ORDER_AND_CHAOS_BOARD.size),
+            GameVariant::Qubic => Self::layered(qubic::SIDE, qubic::SIDE, qubic::LAYERS),
+            GameVariant::Notakto => {
+                Self::layered(notakto::SIDE, notakto::SIDE, rules.notakto_board_count())
+            }
+        }
+    }
+
+    pub fn layers(&self) -> usize {
+        self.cols / self.layer_cols
+    }
+
+    // Width and height of one grid.
+    pub fn layer_pixels(&self) -> Vec2 {
+        Vec2::new(
+            self.cell_size * self.layer_cols as f32,
+            self.cell_size * self.rows as f32,
+        )
+    }
+
+    // Centre of one grid.
+    pub fn layer_center(&self, layer: usize) -> Vec2 {
+        let middle = (self.layers() as f32 - 1.0) / 2.0;
+        let spacing = self.cell_size * (self.layer_cols as f32 + LAYER_GAP_RATIO);
+        self.center + Vec2::new((layer as f32 - middle) * spacing, 0.0)
+    }
+
+    // Centre of a cell.
+    pub fn cell_center(&self, row: usize, col: usize) -> Vec2 {
+        let middle_row = (self.rows as f32 - 1.0) / 2.0;
+        let middle_col = (self.layer_cols as f32 - 1.0) / 2.0;
+        self.layer_center(col / self.layer_cols)
+            + Vec2::new(
+                ((col % self.layer_cols) as f32 - middle_col) * self.cell_size,
+                (middle_row - row as f32) * self.cell_size,
+            )
+    }
+
+    // Scales a length tuned for the classic 3×3 cell to this board's cells.
+    pub fn scaled(&self, length: f32) -> f32 {
+        length * self.cell_size / CELL_SIZE
+    }
+}
+
+// The mark picked up to be moved in the sliding variant.
+#[derive(Resource, Debug, Default)]
+pub struct SelectedMark(pub Option<(usize, usize)>);
+
+// The symbol a human places in wild games and Order and Chaos, chosen with
+// the symbol picker.
+#[derive(Resource, Debug, Default)]
+pub struct SelectedSymbol(pub Player);
+
+// The reserve piece a human places next in Gobblet Gobblers, chosen on the
+// reserve tray; None moves a piece already on the board, or places the
+// largest one left.
+#[derive(Resource, Debug, Default)]
+pub struct SelectedSize(pub Option<PieceSize>);
+
+// Font used by all UI text, loaded once at startup.
+#[derive(Resource)]
+pub struct GameFont(pub Handle<Font>);
+
 #[derive(Resource)]
 pub struct AIDelay {
     pub timer: Timer,
@@ -25,28 +603,90 @@ pub struct AIDelay {
 impl Default for AIDelay {
     fn default() -> Self {
         Self {
-            timer: Timer::from_seconds(1.0, TimerMode::Once),
+            timer: Timer::from_seconds(AI_MOVE_DELAY, TimerMode::Once),
         }
     }
 }
 
 // Default Implementation
 impl Default for BoardState {
+    fn default() -> Self {
+        Self::new(GameRules::default(), Player::X)
+    }
+}
+
+// Which side the human sits on and who opens each game.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeatAssignment {
+    // Side the human plays in Human vs AI; the AI takes the other one.
+    pub human_player: Player,
+    // Side that moves first in the first game of a series.
+    pub first_player: Player,
+    // Swap the opening side after every game of a series.
+    pub alternate_first: bool,
+}
+
+impl Default for SeatAssignment {
     fn default() -> Self {
         Self {
-            board: [[None; 3]; 3],
-            current_player: Player::X,
-            game_over: false,
-            winner: None,
+            human_player: Player::X,
+            first_player: Player::X,
+            alternate_first: false,
+        }
+    }
+}
+
+impl SeatAssignment {
+    // Whether the AI plays for `player` in the given mode.
+    pub fn is_ai_turn(&self, mode: GameMode, player: Player) -> bool {
+        match mode {
+            GameMode::HumanVsHuman => false,
+            GameMode::HumanVsAI => player != self.human_player,
+            GameMode::AIVsAI => true,
+        }
+    }
+
+    // The side a handicap goes to: the human against the AI, otherwise the
+    // side that moves last in the first round of a game opened by `starting`.
+    pub fn weaker_player(&self, mode: GameMode, rules: GameRules, starting: Player) -> Player {
+        if mode == GameMode::HumanVsAI {
+            return self.human_player;
+        }
+        rules
+            .players()
+            .into_iter()
+            .find(|&player| rules.next_player(player) == starting)
+            .unwrap_or(starting)
+    }
+
+    // Side that opens the next game of a series after one opened by `previous`.
+    // With three players the opening passes on in turn order.
+    pub fn next_starting_player(&self, rules: GameRules, previous: Player) -> Player {
+        if self.alternate_first {
+            rules.next_player(previous)
+        } else {
+            self.first_player
         }
     }
 }
 
 // Game Statistics
-#[derive(Resource, Default)]
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct GameStats {
     pub x_wins: u32,
     pub o_wins: u32,
+    // Older saves predate three-player games.
+    #[serde(default)]
+    pub triangle_wins: u32,
+    // Quantum games won 1–½, which count here rather than as full wins.
+    #[serde(default)]
+    pub x_split_wins: u32,
+    #[serde(default)]
+    pub o_split_wins: u32,
     pub draws: u32,
     pub total_games: u32,
 }
+
+// Every completed game, oldest first.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatchHistory(pub Vec<GameRecord>);
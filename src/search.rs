@@ -0,0 +1,272 @@
+use std::fmt::Debug;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::config::{EASY_AI_DEPTH, EASY_AI_RANDOM_CHANCE, HARD_AI_DEPTH, MEDIUM_AI_DEPTH};
+use crate::types::{
+    find_winning_line_through, is_board_full, Board, Difficulty, Player, WinningLine,
+};
+
+// The depth-limited alpha-beta search behind every variant's AI. A variant
+// describes its positions through `Game`: which moves there are, how to play
+// and take them back in place, when the game is over and how good an
+// unfinished position looks. Scores are from the maximizing side's point of
+// view.
+
+// Base score for a won game; wins found deeper in the tree score lower.
+// Large enough that no heuristic total on a 15×15 board can reach it.
+pub const WIN_SCORE: i32 = 10_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    // Won by the maximizing side (`true`) or the minimizing side.
+    Win(bool),
+    // Won while the loser also made a line, scoring them half a point: worth
+    // half a clean win.
+    SplitWin(bool),
+    Draw,
+}
+
+impl Outcome {
+    // The same result with winner and loser swapped, as under misère rules.
+    fn reversed(self) -> Outcome {
+        match self {
+            Outcome::Win(maximizer) => Outcome::Win(!maximizer),
+            Outcome::SplitWin(maximizer) => Outcome::SplitWin(!maximizer),
+            Outcome::Draw => Outcome::Draw,
+        }
+    }
+
+    fn score(self, depth: i32) -> i32 {
+        match self {
+            Outcome::Win(true) => WIN_SCORE - depth,
+            Outcome::Win(false) => depth - WIN_SCORE,
+            Outcome::SplitWin(true) => WIN_SCORE / 2 - depth,
+            Outcome::SplitWin(false) => depth - WIN_SCORE / 2,
+            Outcome::Draw => 0,
+        }
+    }
+}
+
+pub trait Game {
+    type Move: Copy + Debug;
+
+    // Names the variant in the AI's log.
+    const NAME: &'static str;
+    // How many plies Hard looks ahead.
+    const HARD_DEPTH: i32 = HARD_AI_DEPTH;
+
+    // Whether the side to move maximizes the score.
+    fn maximizing(&self) -> bool;
+
+    // Every move the side to move may make.
+    fn legal_moves(&self) -> Vec<Self::Move>;
+
+    // The moves worth searching, best first where the variant can tell.
+    fn search_moves(&self) -> Vec<Self::Move> {
+        self.legal_moves()
+    }
+
+    fn apply(&mut self, game_move: Self::Move);
+
+    // Takes back `game_move`, the last move applied.
+    fn undo(&mut self, game_move: Self::Move);
+
+    // The result once the game is over, before any misère reversal.
+    fn outcome(&self) -> Option<Outcome>;
+
+    // Heuristic score of an unfinished position.
+    fn evaluate(&self) -> i32 {
+        0
+    }
+
+    // Whether making the deciding line loses; reverses the outcome and the
+    // heuristic.
+    fn misere(&self) -> bool {
+        false
+    }
+
+    // The deepest search that stays responsive in this position.
+    fn depth_cap(&self) -> i32 {
+        i32::MAX
+    }
+}
+
+// How many plies the AI looks ahead at each difficulty.
+pub fn search_depth<G: Game>(difficulty: Difficulty) -> i32 {
+    match difficulty {
+        Difficulty::Easy => EASY_AI_DEPTH,
+        Difficulty::Medium => MEDIUM_AI_DEPTH,
+        Difficulty::Hard => G::HARD_DEPTH,
+    }
+}
+
+fn minimax<G: Game>(
+    game: &mut G,
+    depth: i32,
+    max_depth: i32,
+    mut alpha: i32,
+    mut beta: i32,
+) -> i32 {
+    if let Some(outcome) = game.outcome() {
+        let outcome = if game.misere() {
+            outcome.reversed()
+        } else {
+            outcome
+        };
+        return outcome.score(depth);
+    }
+
+    // Out of search depth: fall back to the heuristic
+    if depth >= max_depth {
+        let score = game.evaluate();
+        return if game.misere() { -score } else { score };
+    }
+
+    let moves = game.search_moves();
+    if moves.is_empty() {
+        // No legal move: nothing more can happen
+        return 0;
+    }
+
+    let maximizing = game.maximizing();
+    let mut best = if maximizing { i32::MIN } else { i32::MAX };
+    for game_move in moves {
+        game.apply(game_move);
+        let eval = minimax(game, depth + 1, max_depth, alpha, beta);
+        game.undo(game_move);
+
+        if maximizing {
+            best = best.max(eval);
+            alpha = alpha.max(eval);
+        } else {
+            best = best.min(eval);
+            beta = beta.min(eval);
+        }
+        if beta <= alpha {
+            break; // Alpha-beta pruning
+        }
+    }
+    best
+}
+
+// The best move for the side to move with its score, searching `max_depth`
+// plies ahead (the candidate move counts as the first ply), capped by
+// `Game::depth_cap`.
+pub fn best_move<G: Game>(game: &mut G, max_depth: i32) -> Option<(G::Move, i32)> {
+    let max_depth = max_depth.min(game.depth_cap());
+    let maximizing = game.maximizing();
+    let mut best_move = None;
+    let mut best_score = if maximizing { i32::MIN } else { i32::MAX };
+
+    for game_move in game.search_moves() {
+        // Only a strictly better move matters, so the best so far bounds the rest
+        let (alpha, beta) = if maximizing {
+            (best_score, i32::MAX)
+        } else {
+            (i32::MIN, best_score)
+        };
+        game.apply(game_move);
+        let score = minimax(game, 1, max_depth, alpha, beta);
+        game.undo(game_move);
+
+        let is_better = if maximizing {
+            score > best_score
+        } else {
+            score < best_score
+        };
+        if is_better {
+            best_score = score;
+            best_move = Some(game_move);
+        }
+    }
+
+    println!(
+        "🎯 {} AI (depth {}): {:?} with score {}",
+        G::NAME,
+        max_depth,
+        best_move,
+        best_score
+    );
+    best_move.map(|game_move| (game_move, best_score))
+}
+
+// Picks the AI's move for the given difficulty.
+// Easy plays a random legal move most of the time; otherwise the search depth
+// grows with the difficulty.
+pub fn choose_move<G: Game, R: Rng + ?Sized>(
+    mut game: G,
+    difficulty: Difficulty,
+    rng: &mut R,
+) -> Option<G::Move> {
+    if difficulty == Difficulty::Easy && rng.gen::<f32>() < EASY_AI_RANDOM_CHANCE {
+        let random_move = game.legal_moves().choose(rng).copied();
+        println!("🎲 AI plays a random move: {:?}", random_move);
+        return random_move;
+    }
+
+    best_move(&mut game, search_depth::<G>(difficulty)).map(|(game_move, _)| game_move)
+}
+
+// Under the swap rule, whether the side to move after the opening should take
+// the opening over instead of replying.
+pub fn should_swap<G: Game>(mut game: G, difficulty: Difficulty) -> bool {
+    let depth = search_depth::<G>(difficulty).min(game.depth_cap()).max(2);
+    let scores =
+        [depth - 1, depth].map(|depth| best_move(&mut game, depth).map(|(_, score)| score));
+    reply_is_worse(game.maximizing(), scores)
+}
+
+// Whether the best reply to the opening, scored at two depths one ply apart,
+// leaves the side to move worse off, so that the opening favours whoever
+// holds it. The evaluation at the search horizon favours whoever moved last,
+// hence the average of the two; an even opening is answered as usual.
+fn reply_is_worse(maximizing: bool, scores: [Option<i32>; 2]) -> bool {
+    let [Some(shallow), Some(deep)] = scores else {
+        return false;
+    };
+    let average = shallow / 2 + deep / 2;
+    let for_mover = if maximizing { average } else { -average };
+    println!("🔁 Best reply scores {} for the side to move", for_mover);
+    for_mover < 0
+}
+
+// A board under search, for variants where a move puts one mark in one cell.
+// Marks go down and come back off in place, and only lines through the last
+// mark can have been completed, since the search stops as soon as one is.
+#[derive(Debug, Clone)]
+pub struct SearchBoard {
+    pub board: Board,
+    // Cells marked since the search started, most recent last.
+    played: Vec<(usize, usize)>,
+}
+
+impl SearchBoard {
+    pub fn new(board: &Board) -> Self {
+        SearchBoard {
+            board: board.clone(),
+            played: Vec::new(),
+        }
+    }
+
+    pub fn place(&mut self, (row, col): (usize, usize), mark: Player) {
+        self.board[row][col] = Some(mark);
+        self.played.push((row, col));
+    }
+
+    pub fn take_back(&mut self, (row, col): (usize, usize)) {
+        self.board[row][col] = None;
+        self.played.pop();
+    }
+
+    // The line the last mark completed, if any.
+    pub fn completed_line(&self) -> Option<WinningLine> {
+        let &(row, col) = self.played.last()?;
+        find_winning_line_through(&self.board, row, col)
+    }
+
+    pub fn is_full(&self) -> bool {
+        is_board_full(&self.board)
+    }
+}
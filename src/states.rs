@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+
+// Top-level screens of the app. Systems are scheduled per state in main.rs.
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AppState {
+    #[default]
+    MainMenu,
+    Playing,
+    GameOver,
+}
+
+// Run condition for systems that apply while a board is on screen.
+pub fn in_game(state: Res<State<AppState>>) -> bool {
+    matches!(state.0, AppState::Playing | AppState::GameOver)
+}
@@ -1,13 +1,29 @@
-use crate::ai_logic::get_best_move;
+use std::time::Duration;
+
+use crate::ai_logic::StandardGame;
+use crate::config::{AI_MOVE_DELAY, AI_VS_AI_MOVE_DELAY};
 use crate::events::PlayerMoveEvent;
-use crate::resources::{AIDelay, BoardState, CurrentAIDifficulty};
-use crate::types::{Difficulty, Player};
+use crate::gobblet::GobbletGame;
+use crate::gravity::GravityGame;
+use crate::notakto;
+use crate::order_chaos::OrderChaosGame;
+use crate::quantum::QuantumGame;
+use crate::qubic::QubicGame;
+use crate::resources::{AIDelay, BoardState, CurrentAIDifficulty, CurrentGameMode, SeatAssignment};
+use crate::search::{choose_move, should_swap};
+use crate::three_marks::ThreeMarksGame;
+use crate::three_player::ThreePlayerGame;
+use crate::types::{Difficulty, GameMode, GameVariant, MoveKind, Player};
+use crate::ultimate::UltimateGame;
+use crate::wild::WildGame;
 use bevy::prelude::*;
 
 pub fn ai_make_move(
     time: Res<Time>,
     mut ai_delay: ResMut<AIDelay>,
     board_state: Res<BoardState>,
+    game_mode: Res<CurrentGameMode>,
+    seats: Res<SeatAssignment>,
     ai_difficulty: Res<CurrentAIDifficulty>,
     mut player_move_events: EventWriter<PlayerMoveEvent>,
 ) {
@@ -17,52 +33,171 @@ pub fn ai_make_move(
         return;
     }
 
-    if board_state.current_player != Player::O {
+    let ai_player = board_state.current_player;
+    if !seats.is_ai_turn(game_mode.0, ai_player) {
         ai_delay.timer.reset();
         return;
     }
 
+    // Spectator games move slower so each move can be followed
+    let delay = if game_mode.0 == GameMode::AIVsAI {
+        AI_VS_AI_MOVE_DELAY
+    } else {
+        AI_MOVE_DELAY
+    };
+    ai_delay.timer.set_duration(Duration::from_secs_f32(delay));
+
     // Update the timer
     ai_delay.timer.tick(time.delta());
 
     // Only make a move when the timer finishes (just_finished = only once)
     if ai_delay.timer.just_finished() {
-        println!("🤖 AI making move...");
-
-        // Convert difficulty to depth (for future use)
-        let _depth = match ai_difficulty.0 {
-            Difficulty::Easy => 1,
-            Difficulty::Medium => 3,
-            Difficulty::Hard => 5,
-        };
-
-        // Get best move
-        if let Some(best_move) = get_best_move(&board_state.board, Player::O) {
-            println!("🎯 AI chooses: ({}, {})", best_move.0, best_move.1);
+        println!("🤖 AI making move for {}...", ai_player.to_char());
 
+        // Under the swap rule the opening may be worth more than any reply
+        if wants_swap(&board_state, ai_player, ai_difficulty.0) {
+            let opening = board_state.moves[0];
+            println!("🔁 AI takes over the opening move");
             player_move_events.send(PlayerMoveEvent {
-                position: (best_move.0, best_move.1),
+                position: (opening.row, opening.col),
+                kind: MoveKind::Swap,
             });
-        } else {
-            println!("❌ AI couldn't find a move! Looking for any empty cell...");
-
-            // Fallback to any empty cell
-            for row in 0..3 {
-                for col in 0..3 {
-                    if board_state.board[row][col].is_none() {
-                        println!("🔄 AI fallback move: ({}, {})", row, col);
+            ai_delay.timer.reset();
+            return;
+        }
 
-                        player_move_events.send(PlayerMoveEvent {
-                            position: (row, col),
-                        });
-                        return;
-                    }
-                }
-            }
-            println!("💀 No empty cells found!");
+        // Get a move for the selected difficulty
+        let mut rng = rand::thread_rng();
+        let variant = board_state.rules.variant;
+        let place_new = |cell: Option<(usize, usize)>| {
+            cell.map(|position| PlayerMoveEvent {
+                position,
+                kind: MoveKind::Place,
+            })
+        };
+        let ai_move = match variant {
+            GameVariant::Standard => place_new(choose_move(
+                StandardGame::new(&board_state.board, ai_player, board_state.rules.misere),
+                ai_difficulty.0,
+                &mut rng,
+            )),
+            GameVariant::Ultimate => place_new(choose_move(
+                UltimateGame::new(
+                    &board_state.board,
+                    board_state.last_move(),
+                    ai_player,
+                    board_state.rules.misere,
+                ),
+                ai_difficulty.0,
+                &mut rng,
+            )),
+            GameVariant::Gravity => place_new(choose_move(
+                GravityGame::new(&board_state.board, ai_player, board_state.rules.misere),
+                ai_difficulty.0,
+                &mut rng,
+            )),
+            GameVariant::Qubic => place_new(choose_move(
+                QubicGame::new(&board_state.board, ai_player, board_state.rules.misere),
+                ai_difficulty.0,
+                &mut rng,
+            )),
+            GameVariant::Disappearing | GameVariant::Sliding => choose_move(
+                ThreeMarksGame::new(
+                    variant,
+                    &board_state.board,
+                    &board_state.moves,
+                    ai_player,
+                    board_state.rules.misere,
+                ),
+                ai_difficulty.0,
+                &mut rng,
+            )
+            .map(|mark_move| PlayerMoveEvent {
+                position: mark_move.to,
+                kind: mark_move
+                    .from
+                    .map_or(MoveKind::Place, |from| MoveKind::Slide { from }),
+            }),
+            GameVariant::Wild => choose_move(
+                WildGame::new(&board_state.board, ai_player, board_state.rules.misere),
+                ai_difficulty.0,
+                &mut rng,
+            )
+            .map(|(position, symbol)| PlayerMoveEvent {
+                position,
+                kind: MoveKind::Wild { symbol },
+            }),
+            GameVariant::Notakto => place_new(notakto::choose_move(
+                &board_state.board,
+                ai_player,
+                ai_difficulty.0,
+                &mut rng,
+            )),
+            GameVariant::Quantum => choose_move(
+                QuantumGame::new(board_state.quantum(), board_state.rules.misere),
+                ai_difficulty.0,
+                &mut rng,
+            )
+            .map(|quantum_move| PlayerMoveEvent {
+                position: quantum_move.cell,
+                kind: quantum_move
+                    .partner
+                    .map_or(MoveKind::Place, |partner| MoveKind::Quantum { partner }),
+            }),
+            GameVariant::Gobblet => choose_move(
+                GobbletGame::new(board_state.gobblet(), board_state.rules.misere),
+                ai_difficulty.0,
+                &mut rng,
+            )
+            .map(|gobblet_move| PlayerMoveEvent {
+                position: gobblet_move.to,
+                kind: MoveKind::Gobblet {
+                    size: gobblet_move.size,
+                    from: gobblet_move.from,
+                },
+            }),
+            GameVariant::OrderAndChaos => board_state
+                .role_of(ai_player)
+                .and_then(|role| {
+                    choose_move(
+                        OrderChaosGame::new(&board_state.board, role),
+                        ai_difficulty.0,
+                        &mut rng,
+                    )
+                })
+                .map(|(position, symbol)| PlayerMoveEvent {
+                    position,
+                    kind: MoveKind::Wild { symbol },
+                }),
+            GameVariant::ThreePlayer => place_new(choose_move(
+                ThreePlayerGame::new(&board_state.board, ai_player, board_state.rules.turn_order),
+                ai_difficulty.0,
+                &mut rng,
+            )),
+        };
+        if let Some(event) = ai_move {
+            println!("🎯 AI chooses: {:?}", event);
+            player_move_events.send(event);
+        } else {
+            println!("💀 AI couldn't find a move - no empty cells found!");
         }
 
         // Reset timer after making a move (or trying to)
         ai_delay.timer.reset();
     }
 }
+
+// Whether the AI, to move after the opening, takes it over under the swap rule.
+fn wants_swap(board_state: &BoardState, player: Player, difficulty: Difficulty) -> bool {
+    // Only the variants `GameVariant::allows_swap` names get this far
+    if !board_state.can_swap() {
+        return false;
+    }
+    let board = &board_state.board;
+    let misere = board_state.rules.misere;
+    if board_state.rules.variant == GameVariant::Gravity {
+        should_swap(GravityGame::new(board, player, misere), difficulty)
+    } else {
+        should_swap(StandardGame::new(board, player, misere), difficulty)
+    }
+}
@@ -1,16 +1,26 @@
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, TAU};
+
 use bevy::prelude::*;
 
-use crate::components::{BoardPosition, CellMark};
-use crate::config::{CELL_SIZE, MARKER_SIZE_RATIO, O_COLOR, X_COLOR};
+use crate::components::{BoardPosition, CellMark, FallingMark, GameBoard, QuantumMark};
+use crate::config::{
+    ANIMATION_DURATION, FONT_SIZE_HUD, MARKER_SIZE_RATIO, MARKER_THICKNESS, O_COLOR,
+    SELECTED_MARK_COLOR, TRIANGLE_COLOR, X_COLOR,
+};
 use crate::events::{GameOverEvent, PlayerMoveEvent};
-use crate::resources::BoardState;
-use crate::types::Player;
+use crate::gobblet::PieceSize;
+use crate::quantum;
+use crate::resources::{BoardLayout, BoardState, GameFont};
+use crate::states::AppState;
+use crate::types::{GameVariant, MoveKind, Player};
 
 pub fn apply_player_move(
     mut commands: Commands,
     mut board_state: ResMut<BoardState>,
+    layout: Res<BoardLayout>,
     mut player_move_events: EventReader<PlayerMoveEvent>,
-    q_board_cells: Query<(Entity, &BoardPosition, &Transform)>,
+    q_board_cells: Query<(Entity, &BoardPosition, Option<&Children>)>,
+    q_cell_marks: Query<(), With<CellMark>>,
 ) {
     let event_count = player_move_events.len();
     if event_count > 0 {
@@ -18,14 +28,14 @@ pub fn apply_player_move(
     }
 
     for event in player_move_events.iter() {
-        let (row, col) = event.position;
+        let (target_row, target_col) = event.position;
         let player = board_state.current_player;
 
         info!(
             "🎯 Processing move: {} at ({},{})",
             player.to_char(),
-            row,
-            col
+            target_row,
+            target_col
         );
 
         // Check if game is over or cell is occupied
@@ -34,15 +44,47 @@ pub fn apply_player_move(
             continue;
         }
 
-        if board_state.board[row][col].is_some() {
-            info!("🚫 Cell ({}, {}) already occupied", row, col);
+        // Taking over the opening redraws its mark in the new owner's symbol
+        if event.kind == MoveKind::Swap {
+            if !board_state.is_legal(event.position, MoveKind::Swap) {
+                info!("🚫 Swapping is not allowed now");
+                continue;
+            }
+            let record = board_state.play(event.position, MoveKind::Swap);
+            let cell = (record.row, record.col);
+            despawn_cell_mark(&mut commands, &q_board_cells, &q_cell_marks, cell);
+            for (cell_entity, pos, _) in q_board_cells.iter() {
+                if (pos.row, pos.col) == cell {
+                    spawn_cell_mark(&mut commands, cell_entity, record.mark(), &layout);
+                }
+            }
+            info!(
+                "🔁 {} takes over the opening move at ({}, {})",
+                player.to_char(),
+                record.row,
+                record.col
+            );
+            continue;
+        }
+
+        // Under gravity the mark falls to the lowest empty cell of the column
+        let Some((row, col)) = board_state.landing_cell(target_row, target_col) else {
+            info!("🚫 Column {} is full", target_col);
+            continue;
+        };
+
+        if !board_state.is_legal((row, col), event.kind) {
+            info!(
+                "🚫 Cell ({}, {}) with {:?} is not a legal move",
+                row, col, event.kind
+            );
             continue;
         }
 
         // Find the correct cell entity
         let mut found_cell_entity = None;
 
-        for (entity, pos, _transform) in q_board_cells.iter() {
+        for (entity, pos, _) in q_board_cells.iter() {
             if pos.row == row && pos.col == col {
                 found_cell_entity = Some(entity);
                 break;
@@ -56,59 +98,53 @@ pub fn apply_player_move(
 
         info!("📍 Found cell entity for ({},{})", row, col);
 
-        board_state.board[row][col] = Some(player);
+        let record = board_state.play((row, col), event.kind);
 
-        // Calculate marker properties
-        let mark_font_size = CELL_SIZE * MARKER_SIZE_RATIO;
-        let mark_color = match player {
-            Player::X => X_COLOR,
-            Player::O => O_COLOR,
-        };
+        // Quantum marks and Gobblet pieces are redrawn from the whole position
+        if board_state.rules.variant.redraws_marks() {
+            info!("✅ {} played {:?}", player.to_char(), record);
+            continue;
+        }
 
-        info!(
-            "Font size: {:.2}, CELL_SIZE: {:.2}, MARKER_SIZE_RATIO: {:.2}",
-            mark_font_size, CELL_SIZE, MARKER_SIZE_RATIO
-        );
-        info!("Color for {}: {:?}", player.to_char(), mark_color);
+        // A moved or disappearing mark leaves its old cell
+        if let Some(from) = record.vacated() {
+            despawn_cell_mark(&mut commands, &q_board_cells, &q_cell_marks, from);
+        }
 
-        // Spawn the marker entity
-        let marker_entity = commands
-            .spawn((
-                SpriteBundle {
-                    sprite: Sprite {
-                        color: match player {
-                            Player::X => Color::rgb(1.0, 0.0, 0.0),
-                            Player::O => Color::rgb(0.0, 0.1, 0.0),
-                        },
-                        custom_size: Some(Vec2::new(80.0, 80.0)),
-                        ..default()
-                    },
-                    transform: Transform::from_xyz(0.0, 0.0, 100.0),
-                    ..default()
+        let marker_entity = spawn_cell_mark(&mut commands, cell_entity, record.mark(), &layout);
+        if board_state.rules.variant == GameVariant::Gravity {
+            // Start the mark above the top of the column and let it fall into place
+            let drop_height = layout.cell_size * (row + 1) as f32;
+            commands.entity(marker_entity).insert((
+                Transform::from_xyz(0.0, drop_height, 100.0),
+                FallingMark {
+                    drop_height,
+                    timer: Timer::from_seconds(ANIMATION_DURATION, TimerMode::Once),
                 },
-                CellMark(player),
-            ))
-            .id();
-        // Make the marker a child of the cell
-        commands.entity(cell_entity).add_child(marker_entity);
-
-        info!("✅ {} marker spawned at ({}, {}) with entity {:?} using color {:?} and font size {:.1}",
-              player.to_char(), row, col, marker_entity, mark_color, mark_font_size);
+            ));
+        }
+        info!(
+            "✅ {} marker spawned at ({}, {}) with entity {:?}",
+            player.to_char(),
+            row,
+            col,
+            marker_entity
+        );
 
-        // Switch turns ONLY after a successful move
-        board_state.current_player = board_state.current_player.opposite();
+        // Turn switches ONLY after a successful move
         info!(
             "🔄 Turn switched to: {}",
             board_state.current_player.to_char()
         );
 
         println!("Current board state:");
-        for row in 0..3 {
-            for col in 0..3 {
-                match board_state.board[row][col] {
+        for board_row in board_state.board.rows() {
+            for cell in board_row {
+                match cell {
                     None => print!(" . "),
                     Some(Player::X) => print!(" X "),
                     Some(Player::O) => print!(" O "),
+                    Some(Player::Triangle) => print!(" Δ "),
                 }
             }
             println!();
@@ -117,32 +153,365 @@ pub fn apply_player_move(
     }
 }
 
+// Removes the mark drawn in the given cell, if any.
+pub fn despawn_cell_mark(
+    commands: &mut Commands,
+    q_board_cells: &Query<(Entity, &BoardPosition, Option<&Children>)>,
+    q_cell_marks: &Query<(), With<CellMark>>,
+    (row, col): (usize, usize),
+) {
+    for (_, pos, children) in q_board_cells.iter() {
+        if pos.row != row || pos.col != col {
+            continue;
+        }
+        for &child in children.into_iter().flatten() {
+            if q_cell_marks.contains(child) {
+                commands.entity(child).despawn_recursive();
+            }
+        }
+    }
+}
+
+// Redraws a quantum game whenever it changes: classical marks as usual with
+// their move number beside them, spooky marks as small subscripted symbols in
+// one of nine slots of their cells (slot = move number), and a tint on the two
+// cells of a mark waiting to be collapsed.
+#[allow(clippy::too_many_arguments)]
+pub fn update_quantum_marks(
+    mut commands: Commands,
+    board_state: Res<BoardState>,
+    layout: Res<BoardLayout>,
+    font: Res<GameFont>,
+    q_board_cells: Query<(Entity, &BoardPosition, Option<&Children>)>,
+    q_cell_marks: Query<(), With<CellMark>>,
+    q_quantum_marks: Query<Entity, With<QuantumMark>>,
+) {
+    if !board_state.is_changed() {
+        return;
+    }
+    for entity in q_quantum_marks.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if board_state.rules.variant != GameVariant::Quantum {
+        return;
+    }
+
+    let state = board_state.quantum();
+    let color = |player: Player| match player {
+        Player::X => X_COLOR,
+        Player::O => O_COLOR,
+        Player::Triangle => TRIANGLE_COLOR,
+    };
+    let text = |value: String, player: Player, font_size: f32, position: Vec2| {
+        (
+            Text2dBundle {
+                text: Text::from_section(
+                    value,
+                    TextStyle {
+                        font: font.0.clone(),
+                        font_size,
+                        color: color(player),
+                    },
+                ),
+                transform: Transform::from_translation(position.extend(110.0)),
+                ..default()
+            },
+            QuantumMark,
+            GameBoard,
+        )
+    };
+
+    for (cell_entity, pos, _) in q_board_cells.iter() {
+        let (row, col) = (pos.row, pos.col);
+        despawn_cell_mark(&mut commands, &q_board_cells, &q_cell_marks, (row, col));
+        if let Some((player, turn)) = state.classical(row, col) {
+            spawn_cell_mark(&mut commands, cell_entity, player, &layout);
+            let corner = layout.cell_center(row, col) + Vec2::splat(layout.cell_size * 0.35);
+            commands.spawn(text(
+                quantum::subscript(turn),
+                player,
+                layout.scaled(FONT_SIZE_HUD),
+                corner,
+            ));
+        }
+    }
+
+    let slot_size = layout.cell_size / 3.0;
+    for mark in state.spooky_marks() {
+        let slot = (mark.turn - 1) % 9;
+        let offset = Vec2::new((slot % 3) as f32 - 1.0, 1.0 - (slot / 3) as f32) * slot_size;
+        for &(row, col) in mark.cells.iter() {
+            commands.spawn(text(
+                format!("{}{}", mark.player.to_char(), quantum::subscript(mark.turn)),
+                mark.player,
+                layout.scaled(FONT_SIZE_HUD),
+                layout.cell_center(row, col) + offset,
+            ));
+        }
+    }
+
+    if let Some(mark) = state.pending_collapse() {
+        for &(row, col) in mark.cells.iter() {
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: SELECTED_MARK_COLOR,
+                        custom_size: Some(Vec2::splat(layout.cell_size)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(
+                        layout.cell_center(row, col).extend(5.0),
+                    ),
+                    ..default()
+                },
+                QuantumMark,
+                GameBoard,
+            ));
+        }
+    }
+}
+
+// Redraws a Gobblet Gobblers game whenever it changes: the top piece of each
+// stack, sized by its piece.
+pub fn update_gobblet_pieces(
+    mut commands: Commands,
+    board_state: Res<BoardState>,
+    layout: Res<BoardLayout>,
+    q_board_cells: Query<(Entity, &BoardPosition, Option<&Children>)>,
+    q_cell_marks: Query<(), With<CellMark>>,
+) {
+    if !board_state.is_changed() || board_state.rules.variant != GameVariant::Gobblet {
+        return;
+    }
+
+    let state = board_state.gobblet();
+    for (cell_entity, pos, _) in q_board_cells.iter() {
+        despawn_cell_mark(
+            &mut commands,
+            &q_board_cells,
+            &q_cell_marks,
+            (pos.row, pos.col),
+        );
+        if let Some(piece) = state.top(pos.row, pos.col) {
+            spawn_mark(
+                &mut commands,
+                cell_entity,
+                piece.player,
+                Some(piece.size),
+                &layout,
+            );
+        }
+    }
+}
+
+// Moves marks dropped under gravity down into their cell, speeding up as they fall.
+pub fn animate_falling_marks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_falling: Query<(Entity, &mut FallingMark, &mut Transform)>,
+) {
+    for (entity, mut falling, mut transform) in q_falling.iter_mut() {
+        falling.timer.tick(time.delta());
+        let progress = falling.timer.percent();
+        transform.translation.y = falling.drop_height * (1.0 - progress * progress);
+
+        if falling.timer.finished() {
+            commands.entity(entity).remove::<FallingMark>();
+        }
+    }
+}
+
+// Number of straight segments used to draw the O ring.
+const RING_SEGMENTS: usize = 36;
+
+// Spawns the glyph for `symbol` as a child of the given cell entity, sized
+// to the board's cells. The symbol is usually, but not always (wild games),
+// the mover's own.
+pub fn spawn_cell_mark(
+    commands: &mut Commands,
+    cell_entity: Entity,
+    symbol: Player,
+    layout: &BoardLayout,
+) -> Entity {
+    spawn_mark(commands, cell_entity, symbol, None, layout)
+}
+
+// Like `spawn_cell_mark`, drawing a Gobblet Gobblers piece to its size.
+fn spawn_mark(
+    commands: &mut Commands,
+    cell_entity: Entity,
+    symbol: Player,
+    size: Option<PieceSize>,
+    layout: &BoardLayout,
+) -> Entity {
+    // Calculate marker properties
+    let size_ratio = size.map_or(MARKER_SIZE_RATIO, PieceSize::scale);
+    let mark_size = layout.cell_size * size_ratio;
+    let thickness = layout.scaled(MARKER_THICKNESS);
+    let mark_color = match symbol {
+        Player::X => X_COLOR,
+        Player::O => O_COLOR,
+        Player::Triangle => TRIANGLE_COLOR,
+    };
+
+    info!(
+        "Mark size: {:.2}, cell size: {:.2}, MARKER_SIZE_RATIO: {:.2}",
+        mark_size, layout.cell_size, MARKER_SIZE_RATIO
+    );
+    info!("Color for {}: {:?}", symbol.to_char(), mark_color);
+
+    // Spawn the marker entity; the glyph is built from bar sprites below it
+    let marker_entity = commands
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, 100.0)),
+            CellMark(symbol, size),
+        ))
+        .with_children(|mark| match symbol {
+            Player::X => spawn_cross(mark, mark_size, thickness, mark_color),
+            Player::O => spawn_ring(mark, mark_size, thickness, mark_color),
+            Player::Triangle => spawn_triangle(mark, mark_size, thickness, mark_color),
+        })
+        .id();
+    // Make the marker a child of the cell
+    commands.entity(cell_entity).add_child(marker_entity);
+    marker_entity
+}
+
+fn spawn_bar(
+    parent: &mut ChildBuilder,
+    length: f32,
+    thickness: f32,
+    color: Color,
+    transform: Transform,
+) {
+    parent.spawn(SpriteBundle {
+        sprite: Sprite {
+            color,
+            custom_size: Some(Vec2::new(length, thickness)),
+            ..default()
+        },
+        transform,
+        ..default()
+    });
+}
+
+// Two diagonal bars spanning a `size` x `size` square.
+fn spawn_cross(parent: &mut ChildBuilder, size: f32, thickness: f32, color: Color) {
+    let length = size * std::f32::consts::SQRT_2;
+    for angle in [FRAC_PI_4, -FRAC_PI_4] {
+        spawn_bar(
+            parent,
+            length,
+            thickness,
+            color,
+            Transform::from_rotation(Quat::from_rotation_z(angle)),
+        );
+    }
+}
+
+// A ring of diameter `size`, approximated by short bars around the circle.
+fn spawn_ring(parent: &mut ChildBuilder, size: f32, thickness: f32, color: Color) {
+    let radius = (size - thickness) / 2.0;
+    // Slightly longer than the arc so neighbouring segments overlap
+    let segment_length = TAU * radius / RING_SEGMENTS as f32 * 1.2;
+
+    for segment in 0..RING_SEGMENTS {
+        let angle = TAU * segment as f32 / RING_SEGMENTS as f32;
+        spawn_bar(
+            parent,
+            segment_length,
+            thickness,
+            color,
+            Transform {
+                translation: Vec3::new(radius * angle.cos(), radius * angle.sin(), 0.0),
+                rotation: Quat::from_rotation_z(angle + FRAC_PI_2),
+                ..default()
+            },
+        );
+    }
+}
+
+// An equilateral triangle, point up, as wide as a `size` x `size` square.
+fn spawn_triangle(parent: &mut ChildBuilder, size: f32, thickness: f32, color: Color) {
+    let height = size * 3_f32.sqrt() / 2.0;
+    let corners = [
+        Vec2::new(0.0, height / 2.0),
+        Vec2::new(size / 2.0, -height / 2.0),
+        Vec2::new(-size / 2.0, -height / 2.0),
+    ];
+
+    for (index, &start) in corners.iter().enumerate() {
+        let end = corners[(index + 1) % corners.len()];
+        let side = end - start;
+        spawn_bar(
+            parent,
+            // Longer than the side so the corners close up
+            side.length() + thickness,
+            thickness,
+            color,
+            Transform {
+                translation: ((start + end) / 2.0).extend(0.0),
+                rotation: Quat::from_rotation_z(side.y.atan2(side.x)),
+                ..default()
+            },
+        );
+    }
+}
+
 pub fn check_game_state(
     mut board_state: ResMut<BoardState>,
     mut game_over_events: EventWriter<GameOverEvent>,
+    mut next_state: ResMut<NextState<AppState>>,
 ) {
     if board_state.game_over {
         return;
     }
 
-    let result = crate::ai_logic::get_game_result(&board_state.board);
+    let result = board_state.winning_line();
 
     match result {
-        Some(winner) => {
-            info!("🏆 {} WINS!", winner.to_char());
+        Some(line) => {
+            // Under misère rules the player who completed the line loses
+            let winner = board_state.rules.line_winner(line.player);
+            info!(
+                "🏆 {} WINS ({} completed {:?})!",
+                winner.to_char(),
+                line.player.to_char(),
+                line.kind
+            );
             board_state.game_over = true;
             board_state.winner = Some(winner);
             game_over_events.send(GameOverEvent {
                 winner: Some(winner),
+                winning_line: Some(line),
+                split: board_state.is_split_win(),
             });
+            next_state.set(AppState::GameOver);
         }
         None => {
+            // Chaos wins Order and Chaos by filling the board
+            if let Some(winner) = board_state.winner_without_line() {
+                info!("🏆 {} WINS (full board)!", winner.to_char());
+                board_state.game_over = true;
+                board_state.winner = Some(winner);
+                game_over_events.send(GameOverEvent {
+                    winner: Some(winner),
+                    winning_line: None,
+                    split: false,
+                });
+                next_state.set(AppState::GameOver);
+            }
             // Check if board is full (draw)
-            if crate::types::is_board_full(&board_state.board) {
+            else if board_state.is_draw() {
                 info!("🤝 DRAW!");
                 board_state.game_over = true;
                 board_state.winner = None;
-                game_over_events.send(GameOverEvent { winner: None });
+                game_over_events.send(GameOverEvent {
+                    winner: None,
+                    winning_line: None,
+                    split: false,
+                });
+                next_state.set(AppState::GameOver);
             }
             // If no winner and board not full, game continues
         }
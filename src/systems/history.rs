@@ -0,0 +1,210 @@
+use bevy::prelude::*;
+
+use crate::components::{BoardPosition, CellMark, RedoButton, UndoButton};
+use crate::events::GameOverEvent;
+use crate::export::current_timestamp;
+use crate::resources::{
+    AIDelay, BoardLayout, BoardState, CurrentAIDifficulty, CurrentGameMode, MatchHistory,
+    SeatAssignment,
+};
+use crate::systems::gameplay::{despawn_cell_mark, spawn_cell_mark};
+use crate::types::{GameMode, GameRecord, GameResult, MoveKind, MoveRecord, Player};
+
+// Undo: Ctrl+Z (Cmd+Z on macOS). Redo: Ctrl+Y or Ctrl+Shift+Z.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn handle_undo_redo(
+    keys: Res<Input<KeyCode>>,
+    undo_buttons: Query<&Interaction, (Changed<Interaction>, With<UndoButton>)>,
+    redo_buttons: Query<&Interaction, (Changed<Interaction>, With<RedoButton>)>,
+    game_mode: Res<CurrentGameMode>,
+    seats: Res<SeatAssignment>,
+    mut board_state: ResMut<BoardState>,
+    layout: Res<BoardLayout>,
+    mut ai_delay: ResMut<AIDelay>,
+    mut commands: Commands,
+    q_board_cells: Query<(Entity, &BoardPosition, Option<&Children>)>,
+    q_cell_marks: Query<(), With<CellMark>>,
+) {
+    let modifier = keys.any_pressed([
+        KeyCode::LControl,
+        KeyCode::RControl,
+        KeyCode::LWin,
+        KeyCode::RWin,
+    ]);
+    let shift = keys.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    let clicked = |interaction: &Interaction| *interaction == Interaction::Clicked;
+
+    let undo_requested =
+        (modifier && !shift && keys.just_pressed(KeyCode::Z)) || undo_buttons.iter().any(clicked);
+    let redo_requested = (modifier
+        && (keys.just_pressed(KeyCode::Y) || (shift && keys.just_pressed(KeyCode::Z))))
+        || redo_buttons.iter().any(clicked);
+
+    if !undo_requested && !redo_requested {
+        return;
+    }
+
+    // Nobody to take moves back for when the AI plays both sides
+    if game_mode.0 == GameMode::AIVsAI {
+        info!("🚫 Undo / redo is not available in AI vs AI");
+        return;
+    }
+
+    let is_ai_turn = |player| seats.is_ai_turn(game_mode.0, player);
+    // Quantum marks and Gobblet pieces are redrawn from the whole position
+    let draw_marks = !board_state.rules.variant.redraws_marks();
+
+    if undo_requested {
+        let undone = board_state.undo_turn(is_ai_turn);
+        if undone.is_empty() {
+            info!("🚫 Nothing to undo");
+        }
+        let swap_cell = swap_cell(&undone);
+
+        for record in undone {
+            info!(
+                "↩️ Undo {} at ({}, {})",
+                record.player.to_char(),
+                record.row,
+                record.col
+            );
+            if !draw_marks || swap_cell == Some((record.row, record.col)) {
+                continue;
+            }
+            despawn_cell_mark(
+                &mut commands,
+                &q_board_cells,
+                &q_cell_marks,
+                (record.row, record.col),
+            );
+            if let Some(from) = record.vacated() {
+                spawn_mark_at(&mut commands, &q_board_cells, record.mark(), &layout, from);
+            }
+        }
+        redraw_swap_cell(
+            &mut commands,
+            &q_board_cells,
+            &q_cell_marks,
+            &board_state,
+            &layout,
+            swap_cell,
+        );
+    } else {
+        let redone = board_state.redo_turn(is_ai_turn);
+        if redone.is_empty() {
+            info!("🚫 Nothing to redo");
+        }
+        let swap_cell = swap_cell(&redone);
+
+        for record in redone {
+            info!(
+                "↪️ Redo {} at ({}, {})",
+                record.player.to_char(),
+                record.row,
+                record.col
+            );
+            if !draw_marks || swap_cell == Some((record.row, record.col)) {
+                continue;
+            }
+            if let Some(from) = record.vacated() {
+                despawn_cell_mark(&mut commands, &q_board_cells, &q_cell_marks, from);
+            }
+            spawn_mark_at(
+                &mut commands,
+                &q_board_cells,
+                record.mark(),
+                &layout,
+                (record.row, record.col),
+            );
+        }
+        redraw_swap_cell(
+            &mut commands,
+            &q_board_cells,
+            &q_cell_marks,
+            &board_state,
+            &layout,
+            swap_cell,
+        );
+    }
+
+    // Give the AI its full thinking delay again if it is now to move
+    ai_delay.timer.reset();
+}
+
+// The opening cell, if a swap is among the moves taken back or replayed.
+// The opening and the swap share it, so it is redrawn once at the end.
+fn swap_cell(records: &[MoveRecord]) -> Option<(usize, usize)> {
+    records
+        .iter()
+        .find(|record| record.kind == MoveKind::Swap)
+        .map(|record| (record.row, record.col))
+}
+
+fn redraw_swap_cell(
+    commands: &mut Commands,
+    q_board_cells: &Query<(Entity, &BoardPosition, Option<&Children>)>,
+    q_cell_marks: &Query<(), With<CellMark>>,
+    board_state: &BoardState,
+    layout: &BoardLayout,
+    swap_cell: Option<(usize, usize)>,
+) {
+    let Some((row, col)) = swap_cell else {
+        return;
+    };
+    despawn_cell_mark(commands, q_board_cells, q_cell_marks, (row, col));
+    if let Some(mark) = board_state.board[row][col] {
+        spawn_mark_at(commands, q_board_cells, mark, layout, (row, col));
+    }
+}
+
+fn spawn_mark_at(
+    commands: &mut Commands,
+    q_board_cells: &Query<(Entity, &BoardPosition, Option<&Children>)>,
+    mark: Player,
+    layout: &BoardLayout,
+    (row, col): (usize, usize),
+) {
+    for (cell_entity, pos, _) in q_board_cells.iter() {
+        if pos.row == row && pos.col == col {
+            spawn_cell_mark(commands, cell_entity, mark, layout);
+        }
+    }
+}
+
+// Adds each finished game to the match history used by exports.
+pub fn record_completed_game(
+    mut game_over_events: EventReader<GameOverEvent>,
+    board_state: Res<BoardState>,
+    game_mode: Res<CurrentGameMode>,
+    ai_difficulty: Res<CurrentAIDifficulty>,
+    seats: Res<SeatAssignment>,
+    mut match_history: ResMut<MatchHistory>,
+) {
+    for event in game_over_events.iter() {
+        let record = GameRecord {
+            timestamp: current_timestamp(),
+            game_mode: game_mode.0,
+            difficulty: ai_difficulty.0,
+            human_player: seats.human_player,
+            first_player: board_state.starting_player,
+            rules: board_state.rules,
+            start: board_state.start.clone(),
+            moves: board_state.moves.clone(),
+            result: match event.winner {
+                Some(player) if event.split => GameResult::SplitWin(player),
+                Some(player) => match board_state.role_of(player) {
+                    Some(role) => GameResult::RoleWin(role, player),
+                    None => GameResult::Win(player),
+                },
+                None => GameResult::Draw,
+            },
+        };
+
+        info!(
+            "📝 Recorded game #{}: {}",
+            match_history.0.len() + 1,
+            record.result.label()
+        );
+        match_history.0.push(record);
+    }
+}
@@ -1,17 +1,29 @@
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 
-use crate::components::BoardPosition;
-use crate::config::CELL_SIZE;
+use crate::components::{BlockedCell, BoardPosition};
 use crate::events::PlayerMoveEvent;
-use crate::resources::BoardState;
+use crate::gobblet::PieceSize;
+use crate::resources::{
+    AIDelay, BoardLayout, BoardState, CurrentGameMode, CurrentSetup, SeatAssignment, SelectedMark,
+    SelectedSize, SelectedSymbol,
+};
+use crate::systems::setup::spawn_blocked_cell;
+use crate::types::{GameVariant, MoveKind, Player};
 
+#[allow(clippy::too_many_arguments)]
 pub fn handle_mouse_clicks(
     mouse_button_input: Res<Input<MouseButton>>,
     q_windows: Query<&Window, With<PrimaryWindow>>,
     q_camera: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
     q_board_cells: Query<(&BoardPosition, &Transform)>,
     board_state: Res<BoardState>,
+    layout: Res<BoardLayout>,
+    game_mode: Res<CurrentGameMode>,
+    seats: Res<SeatAssignment>,
+    mut selected_mark: ResMut<SelectedMark>,
+    selected_symbol: Res<SelectedSymbol>,
+    mut selected_size: ResMut<SelectedSize>,
     mut player_move_events: EventWriter<PlayerMoveEvent>,
 ) {
     // Check if clicks are detected at all
@@ -24,6 +36,15 @@ pub fn handle_mouse_clicks(
             return;
         }
 
+        // Only accept clicks for a side a human is playing
+        if seats.is_ai_turn(game_mode.0, board_state.current_player) {
+            info!(
+                "🚫 {:?} is played by the AI, ignoring click",
+                board_state.current_player
+            );
+            return;
+        }
+
         info!(
             "✅ Game not over, current player: {:?}",
             board_state.current_player
@@ -68,7 +89,7 @@ pub fn handle_mouse_clicks(
         for (board_pos, transform) in q_board_cells.iter() {
             let cell_center = transform.translation.truncate();
             let distance = world_position.distance(cell_center);
-            let half_cell = CELL_SIZE / 2.0;
+            let half_cell = layout.cell_size / 2.0;
 
             info!(
                 "🏠 Cell ({},{}) at {:?}, distance: {:.2}, half_cell: {:.2}",
@@ -83,22 +104,55 @@ pub fn handle_mouse_clicks(
             {
                 info!("🎯 CLICKED ON CELL ({},{})!", board_pos.row, board_pos.col);
 
-                // Check if cell is already occupied
-                if board_state.board[board_pos.row][board_pos.col].is_some() {
-                    info!(
-                        "🚫 Cell ({},{}) already occupied",
-                        board_pos.row, board_pos.col
-                    );
+                // With all three marks down in the sliding variant, a click on
+                // one's own mark picks it up and the next click moves it. In
+                // quantum games the first click picks one cell of a spooky mark
+                // and a click on another cell completes it. In Gobblet Gobblers
+                // one's own top piece is picked up the same way, unless a piece
+                // is chosen on the reserve tray, and may then cover another.
+                let cell = (board_pos.row, board_pos.col);
+                let completes_move = selected_mark.0.is_some_and(|selected| {
+                    selected != cell
+                        && selected_move_kind(&board_state, Some(selected), selected_symbol.0, None)
+                            .is_some_and(|kind| board_state.is_legal(cell, kind))
+                });
+                if selected_size.0.is_none()
+                    && board_state.is_selectable(cell.0, cell.1)
+                    && !completes_move
+                {
+                    selected_mark.0 = (selected_mark.0 != Some(cell)).then_some(cell);
+                    info!("✋ Selected mark: {:?}", selected_mark.0);
                     return;
                 }
+                let from = selected_mark.0;
+
+                // Under gravity only the column matters: the mark drops in from the top
+                let (row, col) = if board_state.rules.variant == GameVariant::Gravity {
+                    (0, board_pos.col)
+                } else {
+                    (board_pos.row, board_pos.col)
+                };
 
-                info!(
-                    "✅ Sending PlayerMoveEvent for ({},{})",
-                    board_pos.row, board_pos.col
-                );
+                // Check the mark lands on a free cell (in the right sub-board in Ultimate)
+                let legal_kind =
+                    selected_move_kind(&board_state, from, selected_symbol.0, selected_size.0)
+                        .filter(|&kind| {
+                            board_state
+                                .landing_cell(row, col)
+                                .is_some_and(|landing| board_state.is_legal(landing, kind))
+                        });
+                let Some(kind) = legal_kind else {
+                    info!("🚫 ({},{}) from {:?} is not a legal move", row, col, from);
+                    return;
+                };
+
+                info!("✅ Sending PlayerMoveEvent for ({},{})", row, col);
                 player_move_events.send(PlayerMoveEvent {
-                    position: (board_pos.row, board_pos.col),
+                    position: (row, col),
+                    kind,
                 });
+                selected_mark.0 = None;
+                selected_size.0 = None;
 
                 found_cell = true;
                 break;
@@ -110,3 +164,102 @@ pub fn handle_mouse_clicks(
         }
     }
 }
+
+// The move the current selection makes: `from` is the mark picked up in the
+// sliding variant, the first cell of a spooky mark in quantum games, or the
+// stack a Gobblet piece is lifted from. `symbol` is placed in wild games and
+// Order and Chaos. `size` is the Gobblet piece, by default the one on `from`
+// or the largest left in reserve; None if there is no such piece.
+fn selected_move_kind(
+    board_state: &BoardState,
+    from: Option<(usize, usize)>,
+    symbol: Player,
+    size: Option<PieceSize>,
+) -> Option<MoveKind> {
+    let kind = match board_state.rules.variant {
+        GameVariant::Standard
+        | GameVariant::Ultimate
+        | GameVariant::Qubic
+        | GameVariant::Gravity
+        | GameVariant::Disappearing
+        | GameVariant::Notakto
+        | GameVariant::ThreePlayer => MoveKind::Place,
+        GameVariant::Sliding => from.map_or(MoveKind::Place, |from| MoveKind::Slide { from }),
+        GameVariant::Wild | GameVariant::OrderAndChaos => MoveKind::Wild { symbol },
+        GameVariant::Quantum => {
+            from.map_or(MoveKind::Place, |partner| MoveKind::Quantum { partner })
+        }
+        GameVariant::Gobblet => MoveKind::Gobblet {
+            size: size.or_else(|| board_state.gobblet().default_size(from))?,
+            from,
+        },
+    };
+    Some(kind)
+}
+
+// Right-clicking a cell before the first move blocks it, or opens it again,
+// for the rest of the series. Only plain boards take blocked cells.
+#[allow(clippy::too_many_arguments)]
+pub fn handle_cell_blocking(
+    mouse_button_input: Res<Input<MouseButton>>,
+    q_windows: Query<&Window, With<PrimaryWindow>>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+    q_board_cells: Query<(Entity, &BoardPosition, &Transform, Option<&Children>)>,
+    q_blocked: Query<(), With<BlockedCell>>,
+    mut board_state: ResMut<BoardState>,
+    mut setup: ResMut<CurrentSetup>,
+    layout: Res<BoardLayout>,
+    mut ai_delay: ResMut<AIDelay>,
+    mut commands: Commands,
+) {
+    if !mouse_button_input.just_pressed(MouseButton::Right) || !board_state.moves.is_empty() {
+        return;
+    }
+
+    let Some(world_position) = q_windows
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position())
+        .zip(q_camera.get_single().ok())
+        .and_then(|(cursor, (camera, camera_transform))| {
+            camera.viewport_to_world_2d(camera_transform, cursor)
+        })
+    else {
+        return;
+    };
+
+    let half_cell = layout.cell_size / 2.0;
+    let Some((cell_entity, pos, children)) =
+        q_board_cells
+            .iter()
+            .find_map(|(entity, pos, transform, children)| {
+                let offset = (world_position - transform.translation.truncate()).abs();
+                (offset.x <= half_cell && offset.y <= half_cell).then_some((entity, pos, children))
+            })
+    else {
+        return;
+    };
+
+    let cell = (pos.row, pos.col);
+    let Some(blocked) = board_state.toggle_blocked(cell.0, cell.1) else {
+        info!("🚫 ({},{}) cannot be blocked", cell.0, cell.1);
+        return;
+    };
+
+    if blocked {
+        info!("🧱 Blocked ({},{})", cell.0, cell.1);
+        setup.0.blocked.push(cell);
+        spawn_blocked_cell(&mut commands, cell_entity, &layout);
+    } else {
+        info!("🧱 Opened ({},{})", cell.0, cell.1);
+        setup.0.blocked.retain(|&blocked| blocked != cell);
+        for &child in children.into_iter().flatten() {
+            if q_blocked.contains(child) {
+                commands.entity(child).despawn_recursive();
+            }
+        }
+    }
+
+    // Give the AI its full thinking delay again if it opens the game
+    ai_delay.timer.reset();
+}
@@ -0,0 +1,526 @@
+use bevy::prelude::*;
+
+use crate::components::{
+    AlternateFirstButton, BlockedCellsButton, BoardSizeButton, DifficultyButton, ExportButton,
+    FirstPlayerButton, GameModeButton, HandicapButton, MainMenuUI, MisereButton, OptionButton,
+    SeatButton, StartButton, SwapRuleButton, ThemeButton, ThemedText, TopologyButton,
+    TurnOrderButton, VariantButton,
+};
+use crate::config::{
+    BUTTON_COLOR, BUTTON_HOVER_COLOR, BUTTON_SELECTED_COLOR, BUTTON_WIDTH, FONT_SIZE_STATUS,
+    FONT_SIZE_TITLE, LINE_COLOR,
+};
+use crate::resources::{
+    CurrentAIDifficulty, CurrentGameMode, CurrentRules, CurrentSetup, CurrentTheme, GameFont,
+    SeatAssignment,
+};
+use crate::states::AppState;
+use crate::systems::ui::{spawn_button, spawn_button_with_width};
+use crate::types::{Difficulty, GameMode, GameVariant, Player, Theme, Topology, TurnOrder};
+
+const MENU_LABEL_WIDTH: f32 = 170.0;
+// Buttons holding a single symbol, so a row has room for more options.
+const SYMBOL_BUTTON_WIDTH: f32 = BUTTON_WIDTH / 3.0;
+const SETUP_BUTTON_WIDTH: f32 = BUTTON_WIDTH * 1.2;
+
+pub fn setup_main_menu(
+    mut commands: Commands,
+    font: Res<GameFont>,
+    rules: Res<CurrentRules>,
+    setup: Res<CurrentSetup>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            MainMenuUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "Tic-Tac-Toe",
+                    TextStyle {
+                        font: font.0.clone(),
+                        font_size: FONT_SIZE_TITLE * 1.5,
+                        color: LINE_COLOR,
+                    },
+                ),
+                ThemedText,
+            ));
+
+            spawn_option_row(parent, &font.0, "Game Mode", |row| {
+                for mode in GameMode::ALL {
+                    spawn_button(
+                        row,
+                        &font.0,
+                        mode.label(),
+                        (GameModeButton(mode), OptionButton),
+                    );
+                }
+            });
+
+            spawn_option_row(parent, &font.0, "Rules", |row| {
+                spawn_button(row, &font.0, rules.0.variant.label(), VariantButton);
+                spawn_button_with_width(
+                    row,
+                    &font.0,
+                    &rules.0.board_label(),
+                    BUTTON_WIDTH * 1.5,
+                    BoardSizeButton,
+                );
+            });
+
+            spawn_option_row(parent, &font.0, "A Line", |row| {
+                spawn_button(row, &font.0, "Wins", (MisereButton(false), OptionButton));
+                spawn_button(
+                    row,
+                    &font.0,
+                    "Loses (Misère)",
+                    (MisereButton(true), OptionButton),
+                );
+                spawn_button(
+                    row,
+                    &font.0,
+                    "Wraps Around",
+                    (TopologyButton(Topology::Torus), OptionButton),
+                );
+            });
+
+            spawn_option_row(parent, &font.0, "Setup", |row| {
+                spawn_button_with_width(
+                    row,
+                    &font.0,
+                    &setup.0.random_blocked_label(),
+                    SETUP_BUTTON_WIDTH,
+                    BlockedCellsButton,
+                );
+                spawn_button_with_width(
+                    row,
+                    &font.0,
+                    &setup.0.handicap_label(),
+                    SETUP_BUTTON_WIDTH,
+                    HandicapButton,
+                );
+            });
+
+            spawn_option_row(parent, &font.0, "Turn Order", |row| {
+                for turn_order in TurnOrder::ALL {
+                    spawn_button(
+                        row,
+                        &font.0,
+                        turn_order.label(),
+                        (TurnOrderButton(turn_order), OptionButton),
+                    );
+                }
+                spawn_button(row, &font.0, "Swap Rule", (SwapRuleButton, OptionButton));
+            });
+
+            spawn_option_row(parent, &font.0, "AI Difficulty", |row| {
+                for difficulty in Difficulty::ALL {
+                    spawn_button(
+                        row,
+                        &font.0,
+                        difficulty.label(),
+                        (DifficultyButton(difficulty), OptionButton),
+                    );
+                }
+            });
+
+            spawn_option_row(parent, &font.0, "You Play", |row| {
+                for player in Player::THREE_PLAYERS {
+                    let label = player.to_char().to_string();
+                    spawn_button_with_width(
+                        row,
+                        &font.0,
+                        &label,
+                        SYMBOL_BUTTON_WIDTH,
+                        (SeatButton(player), OptionButton),
+                    );
+                }
+            });
+
+            // Who opens the first game, and who opens the games after it
+            spawn_option_row(parent, &font.0, "First Move", |row| {
+                for player in Player::THREE_PLAYERS {
+                    let label = player.to_char().to_string();
+                    spawn_button_with_width(
+                        row,
+                        &font.0,
+                        &label,
+                        SYMBOL_BUTTON_WIDTH,
+                        (FirstPlayerButton(player), OptionButton),
+                    );
+                }
+                spawn_button(
+                    row,
+                    &font.0,
+                    "Same Opener",
+                    (AlternateFirstButton(false), OptionButton),
+                );
+                spawn_button(
+                    row,
+                    &font.0,
+                    "Alternate",
+                    (AlternateFirstButton(true), OptionButton),
+                );
+            });
+
+            spawn_option_row(parent, &font.0, "Theme", |row| {
+                for theme in Theme::ALL {
+                    spawn_button(
+                        row,
+                        &font.0,
+                        theme.label(),
+                        (ThemeButton(theme), OptionButton),
+                    );
+                }
+            });
+
+            parent.spawn(NodeBundle::default()).with_children(|row| {
+                spawn_button(row, &font.0, "Start", StartButton);
+                spawn_button(row, &font.0, "Export Stats", ExportButton);
+            });
+        });
+
+    info!("📋 Main menu ready");
+}
+
+// A row with a label on the left followed by the option buttons.
+fn spawn_option_row(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    label: &str,
+    spawn_options: impl FnOnce(&mut ChildBuilder),
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|row| {
+            row.spawn((
+                TextBundle::from_section(
+                    label,
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: FONT_SIZE_STATUS,
+                        color: LINE_COLOR,
+                    },
+                )
+                .with_style(Style {
+                    size: Size::width(Val::Px(MENU_LABEL_WIDTH)),
+                    ..default()
+                }),
+                ThemedText,
+            ));
+            spawn_options(row);
+        });
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn handle_menu_buttons(
+    mode_buttons: Query<(&Interaction, &GameModeButton), Changed<Interaction>>,
+    difficulty_buttons: Query<(&Interaction, &DifficultyButton), Changed<Interaction>>,
+    seat_buttons: Query<(&Interaction, &SeatButton), Changed<Interaction>>,
+    first_player_buttons: Query<(&Interaction, &FirstPlayerButton), Changed<Interaction>>,
+    alternate_buttons: Query<(&Interaction, &AlternateFirstButton), Changed<Interaction>>,
+    theme_buttons: Query<(&Interaction, &ThemeButton), Changed<Interaction>>,
+    start_buttons: Query<&Interaction, (Changed<Interaction>, With<StartButton>)>,
+    mut game_mode: ResMut<CurrentGameMode>,
+    mut ai_difficulty: ResMut<CurrentAIDifficulty>,
+    mut seats: ResMut<SeatAssignment>,
+    mut theme: ResMut<CurrentTheme>,
+    rules: Res<CurrentRules>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, button) in mode_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            info!("🎮 Game mode: {:?}", button.0);
+            game_mode.0 = button.0;
+        }
+    }
+
+    for (interaction, button) in difficulty_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            info!("🧠 AI difficulty: {:?}", button.0);
+            ai_difficulty.0 = button.0;
+        }
+    }
+
+    for (interaction, button) in seat_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            info!("🪑 Human plays: {:?}", button.0);
+            seats.human_player = button.0;
+        }
+    }
+
+    for (interaction, button) in first_player_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            info!("🥇 First move: {:?}", button.0);
+            seats.first_player = button.0;
+        }
+    }
+
+    for (interaction, button) in alternate_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            info!("🔁 Alternate first move: {}", button.0);
+            seats.alternate_first = button.0;
+        }
+    }
+
+    for (interaction, button) in theme_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            info!("🎨 Theme: {:?}", button.0);
+            theme.0 = button.0;
+        }
+    }
+
+    if start_buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked)
+    {
+        info!(
+            "▶️ Starting {:?} game on {:?} ({}) with {:?}",
+            game_mode.0,
+            ai_difficulty.0,
+            rules.0.label(),
+            *seats
+        );
+        next_state.set(AppState::Playing);
+    }
+}
+
+// The rules rows: variant, board, edges, misère, setup, turn order and the
+// swap rule. Seats that the selected rules have no place for move to X.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn handle_rules_buttons(
+    misere_buttons: Query<(&Interaction, &MisereButton), Changed<Interaction>>,
+    topology_buttons: Query<(&Interaction, &TopologyButton), Changed<Interaction>>,
+    turn_order_buttons: Query<(&Interaction, &TurnOrderButton), Changed<Interaction>>,
+    swap_buttons: Query<&Interaction, (Changed<Interaction>, With<SwapRuleButton>)>,
+    variant_buttons: Query<&Interaction, (Changed<Interaction>, With<VariantButton>)>,
+    board_buttons: Query<&Interaction, (Changed<Interaction>, With<BoardSizeButton>)>,
+    blocked_buttons: Query<&Interaction, (Changed<Interaction>, With<BlockedCellsButton>)>,
+    handicap_buttons: Query<&Interaction, (Changed<Interaction>, With<HandicapButton>)>,
+    mut seats: ResMut<SeatAssignment>,
+    mut rules: ResMut<CurrentRules>,
+    mut setup: ResMut<CurrentSetup>,
+) {
+    for (interaction, button) in misere_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            info!("🙃 Misère: {}", button.0);
+            rules.0.misere = button.0;
+        }
+    }
+
+    for (interaction, button) in topology_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            rules.0.board.topology = if rules.0.board.topology == button.0 {
+                Topology::Flat
+            } else {
+                button.0
+            };
+            info!("🍩 Edges: {:?}", rules.0.board.topology);
+        }
+    }
+
+    for (interaction, button) in turn_order_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            info!("🔃 Turn order: {}", button.0.label());
+            rules.0.turn_order = button.0;
+        }
+    }
+
+    for interaction in swap_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            rules.0.swap = !rules.0.swap;
+            info!("🔁 Swap rule: {}", rules.0.swap);
+        }
+    }
+
+    for interaction in variant_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            rules.0.variant = rules.0.variant.next();
+            // Three players need a larger board than the one selected
+            if rules.0.board.size < rules.0.variant.min_board_size() {
+                rules.0.board = rules.0.next_board();
+            }
+            info!("🧩 Variant: {}", rules.0.variant.label());
+        }
+    }
+
+    // Notakto cycles its board count; other variants with a fixed board
+    // ignore the preset
+    for interaction in board_buttons.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        if rules.0.variant.uses_board_preset() {
+            rules.0.board = rules.0.next_board();
+            info!("🔲 Board: {}", rules.0.board.label());
+        } else if rules.0.variant == GameVariant::Notakto {
+            rules.0.notakto_boards = rules.0.next_notakto_boards();
+            info!("🔲 Notakto boards: {}", rules.0.notakto_boards);
+        }
+    }
+
+    for interaction in blocked_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            setup.0.random_blocked = setup.0.next_random_blocked();
+            info!("🧱 Setup: {}", setup.0.random_blocked_label());
+        }
+    }
+
+    for interaction in handicap_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            setup.0.handicap = setup.0.next_handicap();
+            info!("🎁 Setup: {}", setup.0.handicap_label());
+        }
+    }
+
+    // Only three-player games seat Δ; any other game gives its seats to X
+    if seats.is_changed() || rules.is_changed() {
+        let players = rules.0.players();
+        if !players.contains(&seats.human_player) {
+            seats.human_player = Player::X;
+        }
+        if !players.contains(&seats.first_player) {
+            seats.first_player = Player::X;
+        }
+    }
+}
+
+// Highlights the selected options; other option buttons show hover state.
+#[allow(clippy::type_complexity)]
+pub fn update_menu_button_colors(
+    game_mode: Res<CurrentGameMode>,
+    ai_difficulty: Res<CurrentAIDifficulty>,
+    seats: Res<SeatAssignment>,
+    theme: Res<CurrentTheme>,
+    rules: Res<CurrentRules>,
+    mut option_buttons: Query<
+        (
+            &Interaction,
+            &mut BackgroundColor,
+            Option<&GameModeButton>,
+            Option<&DifficultyButton>,
+            Option<&SeatButton>,
+            Option<&FirstPlayerButton>,
+            Option<&AlternateFirstButton>,
+            Option<&ThemeButton>,
+            Option<&MisereButton>,
+            Option<&TopologyButton>,
+            Option<&TurnOrderButton>,
+            Option<&SwapRuleButton>,
+        ),
+        With<OptionButton>,
+    >,
+) {
+    for (
+        interaction,
+        mut background,
+        mode,
+        difficulty,
+        seat,
+        first_player,
+        alternate,
+        theme_button,
+        misere,
+        topology,
+        turn_order,
+        swap_rule,
+    ) in option_buttons.iter_mut()
+    {
+        let selected = mode.is_some_and(|button| button.0 == game_mode.0)
+            || difficulty.is_some_and(|button| button.0 == ai_difficulty.0)
+            || seat.is_some_and(|button| button.0 == seats.human_player)
+            || first_player.is_some_and(|button| button.0 == seats.first_player)
+            || alternate.is_some_and(|button| button.0 == seats.alternate_first)
+            || theme_button.is_some_and(|button| button.0 == theme.0)
+            || misere.is_some_and(|button| button.0 == rules.0.misere)
+            || topology.is_some_and(|button| button.0 == rules.0.board.topology)
+            || turn_order.is_some_and(|button| button.0 == rules.0.turn_order)
+            || (swap_rule.is_some() && rules.0.swap);
+
+        *background = selection_color(*interaction, selected).into();
+    }
+}
+
+// Keeps the variant, board and setup buttons' labels in step with the
+// selected rules and setup.
+#[allow(clippy::type_complexity)]
+pub fn update_rules_button_labels(
+    rules: Res<CurrentRules>,
+    setup: Res<CurrentSetup>,
+    q_buttons: Query<
+        (
+            &Children,
+            Option<&VariantButton>,
+            Option<&BlockedCellsButton>,
+            Option<&HandicapButton>,
+        ),
+        Or<(
+            With<VariantButton>,
+            With<BoardSizeButton>,
+            With<BlockedCellsButton>,
+            With<HandicapButton>,
+        )>,
+    >,
+    mut q_text: Query<&mut Text>,
+) {
+    if !rules.is_changed() && !setup.is_changed() {
+        return;
+    }
+
+    for (children, variant, blocked, handicap) in q_buttons.iter() {
+        let label = match (variant, blocked, handicap) {
+            (Some(_), _, _) => rules.0.variant.label().to_string(),
+            (_, Some(_), _) => setup.0.random_blocked_label(),
+            (_, _, Some(_)) => setup.0.handicap_label(),
+            (None, None, None) => rules.0.board_label(),
+        };
+        for &child in children.iter() {
+            if let Ok(mut text) = q_text.get_mut(child) {
+                text.sections[0].value = label.clone();
+            }
+        }
+    }
+}
+
+// Shows the misère buttons only for variants where a line can lose.
+pub fn update_misere_button_visibility(
+    rules: Res<CurrentRules>,
+    mut q_visibility: Query<&mut Visibility, With<MisereButton>>,
+) {
+    let shown = if rules.0.variant.allows_misere() {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for mut visibility in q_visibility.iter_mut() {
+        if *visibility != shown {
+            *visibility = shown;
+        }
+    }
+}
+
+fn selection_color(interaction: Interaction, selected: bool) -> Color {
+    if selected {
+        BUTTON_SELECTED_COLOR
+    } else if interaction == Interaction::None {
+        BUTTON_COLOR
+    } else {
+        BUTTON_HOVER_COLOR
+    }
+}
@@ -1,5 +1,7 @@
 pub mod ai;
 pub mod gameplay;
+pub mod history;
 pub mod input;
+pub mod menu;
 pub mod setup;
 pub mod ui;
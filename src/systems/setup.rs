@@ -1,78 +1,343 @@
 use bevy::prelude::*;
 
-use crate::components::BoardPosition;
-use crate::config::{BACKGROUND_COLOR, BOARD_SIZE, CELL_SIZE, LINE_COLOR, LINE_THICKNESS};
+use crate::components::{
+    BackButton, BlockedCell, BoardPosition, DeadBoardOverlay, GameBoard, GameUI, OptionButton,
+    RedoButton, SizeButton, SubBoardOverlay, SwapButton, SymbolButton, ThemedText, UndoButton,
+};
+use crate::config::{
+    theme_colors, BACKGROUND_COLOR, BLOCKED_CELL_COLOR, BLOCKED_CELL_SIZE_RATIO, FONT_PATH,
+    FONT_SIZE_HUD, LINE_THICKNESS,
+};
+use crate::gobblet::PieceSize;
+use crate::resources::{BoardLayout, BoardState, CurrentRules, CurrentTheme, GameFont};
+use crate::systems::gameplay::spawn_cell_mark;
+use crate::systems::ui::spawn_button;
+use crate::types::{GameVariant, Player};
+use crate::ultimate::sub_board_origin;
 
-pub fn setup_game(mut commands: Commands, mut clear_color: ResMut<ClearColor>) {
-    // Camera
+// Runs once at startup: camera, clear color and shared assets.
+pub fn setup_camera(
+    mut commands: Commands,
+    mut clear_color: ResMut<ClearColor>,
+    asset_server: Res<AssetServer>,
+) {
     commands.spawn((Camera2dBundle::default(),));
     clear_color.0 = BACKGROUND_COLOR;
+    commands.insert_resource(GameFont(asset_server.load(FONT_PATH)));
+}
+
+// Applies the selected theme to the clear color and all themed text.
+pub fn apply_theme(
+    theme: Res<CurrentTheme>,
+    mut clear_color: ResMut<ClearColor>,
+    q_added: Query<(), Added<ThemedText>>,
+    mut q_themed_text: Query<&mut Text, With<ThemedText>>,
+) {
+    if !theme.is_changed() && q_added.is_empty() {
+        return;
+    }
+
+    let colors = theme_colors(theme.0);
+    clear_color.0 = colors.background;
+    for mut text in q_themed_text.iter_mut() {
+        for section in text.sections.iter_mut() {
+            section.style.color = colors.line;
+        }
+    }
+}
+
+// Runs when leaving the main menu, after the board state is reset: spawns
+// the board as the game starts (blocked cells and handicap marks included)
+// and the in-game UI.
+pub fn setup_game(
+    mut commands: Commands,
+    font: Res<GameFont>,
+    theme: Res<CurrentTheme>,
+    rules: Res<CurrentRules>,
+    board_state: Res<BoardState>,
+    mut layout: ResMut<BoardLayout>,
+) {
+    let line_color = theme_colors(theme.0).line;
+    let ultimate = rules.0.variant == GameVariant::Ultimate;
+    let notakto = rules.0.variant == GameVariant::Notakto;
+    *layout = BoardLayout::for_rules(rules.0);
+    let (rows, cols) = (layout.rows, layout.cols);
+    let layer_pixels = layout.layer_pixels();
+
+    // Grid lines (NO BoardPosition components!), one grid per layer
+    for layer in 0..layout.layers() {
+        let layer_center = layout.layer_center(layer);
+
+        for i in 1..layout.layer_cols.max(rows) {
+            // Ultimate draws its sub-board borders at full thickness
+            let line_thickness = if ultimate && i % 3 == 0 {
+                LINE_THICKNESS
+            } else {
+                layout.scaled(LINE_THICKNESS).max(1.0)
+            };
+
+            // Vertical line
+            if i < layout.layer_cols {
+                let offset = (i as f32 - layout.layer_cols as f32 / 2.0) * layout.cell_size;
+                commands.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: line_color,
+                            custom_size: Some(Vec2::new(line_thickness, layer_pixels.y)),
+                            ..default()
+                        },
+                        transform: Transform::from_translation(
+                            (layer_center + Vec2::new(offset, 0.0)).extend(0.0),
+                        ),
+                        ..default()
+                    },
+                    GameBoard,
+                ));
+            }
+
+            // Horizontal line
+            if i < rows {
+                let offset = (i as f32 - rows as f32 / 2.0) * layout.cell_size;
+                commands.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: line_color,
+                            custom_size: Some(Vec2::new(layer_pixels.x, line_thickness)),
+                            ..default()
+                        },
+                        transform: Transform::from_translation(
+                            (layer_center + Vec2::new(0.0, offset)).extend(0.0),
+                        ),
+                        ..default()
+                    },
+                    GameBoard,
+                ));
+            }
+        }
+
+        // Qubic names its layers above each grid, Notakto its boards
+        if layout.layers() > 1 {
+            let label_position = layer_center + Vec2::new(0.0, layer_pixels.y / 2.0 + 20.0);
+            let name = if notakto { "Board" } else { "Layer" };
+            commands.spawn((
+                Text2dBundle {
+                    text: Text::from_section(
+                        format!("{} {}", name, layer + 1),
+                        TextStyle {
+                            font: font.0.clone(),
+                            font_size: FONT_SIZE_HUD,
+                            color: line_color,
+                        },
+                    ),
+                    transform: Transform::from_translation(label_position.extend(0.0)),
+                    ..default()
+                },
+                ThemedText,
+                GameBoard,
+            ));
+        }
+    }
 
-    // Grid lines (NO BoardPosition components!)
-    // Vertical lines
-    commands.spawn(SpriteBundle {
-        sprite: Sprite {
-            color: LINE_COLOR,
-            custom_size: Some(Vec2::new(LINE_THICKNESS, BOARD_SIZE)),
-            ..default()
-        },
-        transform: Transform::from_xyz(-CELL_SIZE / 2.0, 0.0, 0.0),
-        ..default()
-    });
-    commands.spawn(SpriteBundle {
-        sprite: Sprite {
-            color: LINE_COLOR,
-            custom_size: Some(Vec2::new(LINE_THICKNESS, BOARD_SIZE)),
-            ..default()
-        },
-        transform: Transform::from_xyz(CELL_SIZE / 2.0, 0.0, 0.0),
-        ..default()
-    });
-
-    // Horizontal lines
-    commands.spawn(SpriteBundle {
-        sprite: Sprite {
-            color: LINE_COLOR,
-            custom_size: Some(Vec2::new(BOARD_SIZE, LINE_THICKNESS)),
-            ..default()
-        },
-        transform: Transform::from_xyz(0.0, -CELL_SIZE / 2.0, 0.0),
-        ..default()
-    });
-    commands.spawn(SpriteBundle {
-        sprite: Sprite {
-            color: LINE_COLOR,
-            custom_size: Some(Vec2::new(BOARD_SIZE, LINE_THICKNESS)),
-            ..default()
-        },
-        transform: Transform::from_xyz(0.0, CELL_SIZE / 2.0, 0.0),
-        ..default()
-    });
-
-    println!("🏗️ Creating ONLY 9 cells with BoardPosition...");
+    println!(
+        "🏗️ Creating ONLY {} cells with BoardPosition...",
+        rows * cols
+    );
 
     // ONLY cells get BoardPosition - NOTHING ELSE!
-    for row in 0..3 {
-        for col in 0..3 {
-            let x = (col as f32 - 1.0) * CELL_SIZE;
-            let y = (1.0 - row as f32) * CELL_SIZE;
+    for row in 0..rows {
+        for col in 0..cols {
+            let center = layout.cell_center(row, col);
 
-            println!("📍 Cell ({},{}) at ({:.1}, {:.1})", row, col, x, y);
+            let cell_entity = commands
+                .spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::rgba(0.0, 1.0, 0.0, 0.1), // Green tint for debugging
+                            custom_size: Some(Vec2::splat(layout.cell_size)),
+                            ..default()
+                        },
+                        transform: Transform::from_translation(center.extend(0.0)),
+                        ..default()
+                    },
+                    BoardPosition { row, col },
+                    GameBoard,
+                ))
+                .id();
+            spawn_starting_cell(
+                &mut commands,
+                cell_entity,
+                &board_state,
+                (row, col),
+                &layout,
+            );
+        }
+    }
 
+    println!("✅ Created exactly {} cells", rows * cols);
+
+    // One overlay per Ultimate sub-board, recolored as the game goes on
+    if ultimate {
+        for index in 0..9 {
+            let (top, left) = sub_board_origin(index);
+            let center = layout.cell_center(top + 1, left + 1);
             commands.spawn((
                 SpriteBundle {
                     sprite: Sprite {
-                        color: Color::rgba(0.0, 1.0, 0.0, 0.1), // Green tint for debugging
-                        custom_size: Some(Vec2::new(CELL_SIZE, CELL_SIZE)),
+                        color: Color::NONE,
+                        custom_size: Some(Vec2::splat(layout.cell_size * 3.0)),
                         ..default()
                     },
-                    transform: Transform::from_xyz(x, y, 0.0),
+                    transform: Transform::from_translation(center.extend(10.0)),
                     ..default()
                 },
-                BoardPosition { row, col },
+                SubBoardOverlay(index),
+                GameBoard,
             ));
         }
     }
 
-    println!("✅ Created exactly 9 cells");
+    // One overlay per Notakto board, greyed out once the board is dead
+    if notakto {
+        for layer in 0..layout.layers() {
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::NONE,
+                        custom_size: Some(layer_pixels),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(layout.layer_center(layer).extend(10.0)),
+                    ..default()
+                },
+                DeadBoardOverlay(layer),
+                GameBoard,
+            ));
+        }
+    }
+
+    // Back / Undo / Redo buttons in the bottom-left corner, and Swap under
+    // the swap rule
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        left: Val::Px(20.0),
+                        bottom: Val::Px(20.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                ..default()
+            },
+            GameUI,
+        ))
+        .with_children(|parent| {
+            spawn_button(parent, &font.0, "Back", BackButton);
+            spawn_button(parent, &font.0, "Undo", UndoButton);
+            spawn_button(parent, &font.0, "Redo", RedoButton);
+            if rules.0.swap_allowed() {
+                spawn_button(parent, &font.0, "Swap", SwapButton);
+            }
+        });
+
+    // Wild games and Order and Chaos pick the symbol to place in the
+    // bottom-right corner
+    if rules.0.variant.places_either_symbol() {
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        position: UiRect {
+                            right: Val::Px(20.0),
+                            bottom: Val::Px(20.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    ..default()
+                },
+                GameUI,
+            ))
+            .with_children(|parent| {
+                for symbol in Player::ALL {
+                    let label = format!("Place {}", symbol.to_char());
+                    spawn_button(
+                        parent,
+                        &font.0,
+                        &label,
+                        (SymbolButton(symbol), OptionButton),
+                    );
+                }
+            });
+    }
+
+    // Gobblet Gobblers shows the reserve tray in the same corner; the labels
+    // are filled in by `handle_reserve_tray`
+    if rules.0.variant == GameVariant::Gobblet {
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        position: UiRect {
+                            right: Val::Px(20.0),
+                            bottom: Val::Px(20.0),
+                            ..default()
+                        },
+                        flex_direction: FlexDirection::Column,
+                        ..default()
+                    },
+                    ..default()
+                },
+                GameUI,
+            ))
+            .with_children(|parent| {
+                for size in PieceSize::ALL.into_iter().rev() {
+                    spawn_button(
+                        parent,
+                        &font.0,
+                        size.label(),
+                        (SizeButton(size), OptionButton),
+                    );
+                }
+            });
+    }
+}
+
+// Draws what a cell holds when the game starts: a square if nobody may play
+// there, or a handicap mark.
+pub fn spawn_starting_cell(
+    commands: &mut Commands,
+    cell_entity: Entity,
+    board_state: &BoardState,
+    (row, col): (usize, usize),
+    layout: &BoardLayout,
+) {
+    if board_state.board.is_blocked(row, col) {
+        spawn_blocked_cell(commands, cell_entity, layout);
+    } else if let Some(mark) = board_state.board[row][col] {
+        spawn_cell_mark(commands, cell_entity, mark, layout);
+    }
+}
+
+// Spawns the square over a blocked cell as a child of the cell entity.
+pub fn spawn_blocked_cell(commands: &mut Commands, cell_entity: Entity, layout: &BoardLayout) {
+    let block = commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: BLOCKED_CELL_COLOR,
+                    custom_size: Some(Vec2::splat(layout.cell_size * BLOCKED_CELL_SIZE_RATIO)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, 0.0, 1.0),
+                ..default()
+            },
+            BlockedCell,
+        ))
+        .id();
+    commands.entity(cell_entity).add_child(block);
 }
@@ -1,27 +1,300 @@
 use bevy::prelude::*;
 
-use crate::components::{CellMark, GameOverMessage, RestartButton};
-use crate::config::{FONT_SIZE_TITLE, LINE_COLOR, O_COLOR, X_COLOR};
-use crate::events::GameOverEvent;
-use crate::resources::{BoardState, GameStats};
-use crate::types::Player;
+use crate::components::{
+    BackButton, BlockedCell, BoardPosition, CellMark, DeadBoardOverlay, GameBoard, GameInfoText,
+    GameOverMessage, GameUI, MainMenuButton, OptionButton, PlayerTurnIndicator, RestartButton,
+    ScoreboardText, SelectedMarkHighlight, SizeButton, SubBoardOverlay, SwapButton, SymbolButton,
+    WinHighlight,
+};
+use crate::config::{
+    theme_colors, BUTTON_COLOR, BUTTON_HEIGHT, BUTTON_HOVER_COLOR, BUTTON_SELECTED_COLOR,
+    BUTTON_TEXT_COLOR, BUTTON_WIDTH, DRAWN_BOARD_COLOR, FONT_SIZE_BUTTON, FONT_SIZE_HUD,
+    FONT_SIZE_STATUS, FONT_SIZE_TITLE, HUD_WIDTH, LEGAL_BOARD_COLOR, MARKER_SIZE_RATIO, O_COLOR,
+    SELECTED_MARK_COLOR, TRIANGLE_COLOR, WIN_HIGHLIGHT_COLOR, WIN_LINE_THICKNESS, WON_BOARD_ALPHA,
+    X_COLOR,
+};
+use crate::events::{GameOverEvent, PlayerMoveEvent};
+use crate::gobblet::PieceSize;
+use crate::notakto;
+use crate::resources::{
+    AIDelay, BoardLayout, BoardState, CurrentAIDifficulty, CurrentGameMode, CurrentSetup,
+    CurrentTheme, GameFont, GameStats, SeatAssignment, SelectedMark, SelectedSize, SelectedSymbol,
+};
+use crate::states::AppState;
+use crate::systems::setup::spawn_starting_cell;
+use crate::types::{GameMode, GameVariant, MoveKind, Player, WinningLine};
+use crate::ultimate::{sub_board_statuses, target_sub_board, SubBoardStatus};
 
+// Spawns a labelled button as a child of `parent`, tagged with `marker`.
+pub fn spawn_button<B: Bundle>(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    label: &str,
+    marker: B,
+) {
+    spawn_button_with_width(parent, font, label, BUTTON_WIDTH, marker);
+}
+
+// Same as `spawn_button`, for labels that need more (or less) room.
+pub fn spawn_button_with_width<B: Bundle>(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    label: &str,
+    width: f32,
+    marker: B,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    size: Size::new(Val::Px(width), Val::Px(BUTTON_HEIGHT)),
+                    margin: UiRect::all(Val::Px(5.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: BUTTON_COLOR.into(),
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|button| {
+            button.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font: font.clone(),
+                    font_size: FONT_SIZE_BUTTON,
+                    color: BUTTON_TEXT_COLOR,
+                },
+            ));
+        });
+}
+
+// Hover feedback for plain buttons (menu options are colored in systems::menu).
+#[allow(clippy::type_complexity)]
+pub fn button_hover_colors(
+    mut buttons: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>, Without<OptionButton>),
+    >,
+) {
+    for (interaction, mut background) in buttons.iter_mut() {
+        *background = match interaction {
+            Interaction::Hovered | Interaction::Clicked => BUTTON_HOVER_COLOR.into(),
+            Interaction::None => BUTTON_COLOR.into(),
+        };
+    }
+}
+
+// Spawns the scoreboard panel on the right of the board.
+pub fn setup_scoreboard(mut commands: Commands, font: Res<GameFont>, theme: Res<CurrentTheme>) {
+    let colors = theme_colors(theme.0);
+    let hud_style = TextStyle {
+        font: font.0.clone(),
+        font_size: FONT_SIZE_HUD,
+        color: colors.line,
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        right: Val::Px(20.0),
+                        top: Val::Px(160.0),
+                        ..default()
+                    },
+                    size: Size::width(Val::Px(HUD_WIDTH)),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(10.0)),
+                    gap: Size::height(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: colors.panel.into(),
+                ..default()
+            },
+            GameUI,
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: FONT_SIZE_STATUS,
+                        ..hud_style.clone()
+                    },
+                ),
+                PlayerTurnIndicator,
+            ));
+            panel.spawn((
+                TextBundle::from_section("", hud_style.clone()),
+                ScoreboardText,
+            ));
+            panel.spawn((TextBundle::from_section("", hud_style), GameInfoText));
+        });
+}
+
+// Refreshes the scoreboard whenever the stats or the board change.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn update_scoreboard(
+    game_stats: Res<GameStats>,
+    board_state: Res<BoardState>,
+    game_mode: Res<CurrentGameMode>,
+    ai_difficulty: Res<CurrentAIDifficulty>,
+    seats: Res<SeatAssignment>,
+    theme: Res<CurrentTheme>,
+    q_added: Query<(), Added<ScoreboardText>>,
+    mut q_turn: Query<
+        &mut Text,
+        (
+            With<PlayerTurnIndicator>,
+            Without<ScoreboardText>,
+            Without<GameInfoText>,
+        ),
+    >,
+    mut q_stats: Query<&mut Text, (With<ScoreboardText>, Without<GameInfoText>)>,
+    mut q_info: Query<&mut Text, With<GameInfoText>>,
+) {
+    let refresh_all = !q_added.is_empty()
+        || game_mode.is_changed()
+        || ai_difficulty.is_changed()
+        || seats.is_changed();
+    if !refresh_all && !game_stats.is_changed() && !board_state.is_changed() {
+        return;
+    }
+
+    for mut text in q_turn.iter_mut() {
+        let player = board_state.current_player;
+        let section = &mut text.sections[0];
+        if board_state.game_over {
+            section.value = "Game over".to_string();
+            section.style.color = theme_colors(theme.0).line;
+        } else {
+            let owner = if seats.is_ai_turn(game_mode.0, player) {
+                " (AI)"
+            } else {
+                ""
+            };
+            // Sliding games tell the player to pick up a mark rather than place one
+            let action = if board_state.must_move_mark() {
+                "moves a mark"
+            } else if board_state.rules.variant == GameVariant::Quantum
+                && board_state.quantum().pending_collapse().is_some()
+            {
+                "collapses a mark"
+            } else {
+                "to move"
+            };
+            // Order and Chaos names each player's side
+            let name = match board_state.role_of(player) {
+                Some(role) => format!("{} ({})", role.label(), player.to_char()),
+                None => player.to_char().to_string(),
+            };
+            section.value = format!("{} {}{}", name, action, owner);
+            section.style.color = match player {
+                Player::X => X_COLOR,
+                Player::O => O_COLOR,
+                Player::Triangle => TRIANGLE_COLOR,
+            };
+        }
+    }
+
+    // Δ's wins only show once three players have been at the board
+    let triangle_wins =
+        if board_state.rules.variant == GameVariant::ThreePlayer || game_stats.triangle_wins > 0 {
+            format!("Δ wins: {}\n", game_stats.triangle_wins)
+        } else {
+            String::new()
+        };
+    // Likewise quantum wins at 1–½, once there have been any
+    let split_wins = if game_stats.x_split_wins + game_stats.o_split_wins > 0 {
+        format!(
+            "1–½ wins: X {}, O {}\n",
+            game_stats.x_split_wins, game_stats.o_split_wins
+        )
+    } else {
+        String::new()
+    };
+    for mut text in q_stats.iter_mut() {
+        text.sections[0].value = format!(
+            "X wins: {}\nO wins: {}\n{}{}Draws: {}\nGames: {}",
+            game_stats.x_wins,
+            game_stats.o_wins,
+            triangle_wins,
+            split_wins,
+            game_stats.draws,
+            game_stats.total_games
+        );
+    }
+
+    for mut text in q_info.iter_mut() {
+        let mode = match game_mode.0 {
+            GameMode::HumanVsHuman => game_mode.0.label().to_string(),
+            GameMode::HumanVsAI => format!(
+                "{}\n{} AI, you play {}",
+                game_mode.0.label(),
+                ai_difficulty.0.label(),
+                seats.human_player.to_char()
+            ),
+            GameMode::AIVsAI => format!("{}\n{} AI", game_mode.0.label(), ai_difficulty.0.label()),
+        };
+        text.sections[0].value = format!("{}\n{}", mode, board_state.rules.label());
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn display_game_over_ui(
     mut commands: Commands,
     mut game_over_events: EventReader<GameOverEvent>,
     mut game_stats: ResMut<GameStats>,
     mut board_state: ResMut<BoardState>,
+    font: Res<GameFont>,
+    theme: Res<CurrentTheme>,
+    layout: Res<BoardLayout>,
+    q_board_cells: Query<(&BoardPosition, &GlobalTransform)>,
 ) {
     for event in game_over_events.iter() {
         board_state.game_over = true;
 
+        if let Some(line) = &event.winning_line {
+            spawn_win_highlight(&mut commands, line, &layout, &q_board_cells);
+        }
+
         let message_text = match event.winner {
             Some(player) => {
-                match player {
-                    Player::X => game_stats.x_wins += 1,
-                    Player::O => game_stats.o_wins += 1,
+                match (player, event.split) {
+                    (Player::X, false) => game_stats.x_wins += 1,
+                    (Player::O, false) => game_stats.o_wins += 1,
+                    (Player::X, true) => game_stats.x_split_wins += 1,
+                    (Player::O, true) => game_stats.o_split_wins += 1,
+                    (Player::Triangle, _) => game_stats.triangle_wins += 1,
+                }
+                let role = board_state.role_of(player);
+                match (&event.winning_line, role) {
+                    // Order and Chaos: Order makes a line, Chaos fills the board
+                    (Some(_), Some(role)) => format!(
+                        "{} ({}) wins with five in a row! (Press R to restart)",
+                        role.label(),
+                        player.to_char()
+                    ),
+                    (None, Some(role)) => format!(
+                        "{} ({}) wins with a full board! (Press R to restart)",
+                        role.label(),
+                        player.to_char()
+                    ),
+                    // Misère: the line belongs to the loser
+                    (Some(line), None) if line.player != player => format!(
+                        "{} wins, {} made a line! (Press R to restart)",
+                        player.to_char(),
+                        line.player.to_char()
+                    ),
+                    // Quantum: both players made a line in the same collapse
+                    _ if event.split => {
+                        format!("Player {} wins 1–½! (Press R to restart)", player.to_char())
+                    }
+                    _ => format!("Player {} wins! (Press R to restart)", player.to_char()),
                 }
-                format!("Player {} wins! (Press R to restart)", player.to_char())
             }
             None => {
                 game_stats.draws += 1;
@@ -32,77 +305,467 @@ pub fn display_game_over_ui(
 
         info!("Game Over: {}", message_text);
         info!(
-            "Current Stats: X Wins: {}, O Wins: {}, Draws: {}, Total: {}",
-            game_stats.x_wins, game_stats.o_wins, game_stats.draws, game_stats.total_games
+            "Current Stats: X Wins: {} (+{} at 1–½), O Wins: {} (+{} at 1–½), Δ Wins: {}, Draws: {}, Total: {}",
+            game_stats.x_wins,
+            game_stats.x_split_wins,
+            game_stats.o_wins,
+            game_stats.o_split_wins,
+            game_stats.triangle_wins,
+            game_stats.draws,
+            game_stats.total_games
         );
 
         let text_color = match event.winner {
             Some(Player::X) => X_COLOR,
             Some(Player::O) => O_COLOR,
-            None => LINE_COLOR,
+            Some(Player::Triangle) => TRIANGLE_COLOR,
+            None => theme_colors(theme.0).line,
         };
 
-        // Spawn game over message with restart instructions
-        commands.spawn((
-            TextBundle {
-                text: Text::from_section(
+        // Spawn game over message with restart / main menu buttons
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        position: UiRect {
+                            top: Val::Px(20.0),
+                            ..default()
+                        },
+                        size: Size::width(Val::Percent(100.0)),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                },
+                GameOverMessage,
+            ))
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
                     message_text,
                     TextStyle {
-                        font: Handle::default(),
+                        font: font.0.clone(),
                         font_size: FONT_SIZE_TITLE,
                         color: text_color,
                     },
-                ),
-                style: Style {
-                    position_type: PositionType::Absolute,
-                    margin: UiRect {
-                        left: Val::Auto,
-                        right: Val::Auto,
-                        top: Val::Px(50.0),
-                        bottom: Val::Auto,
-                    },
-                    justify_content: JustifyContent::Center,
+                ));
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        spawn_button(row, &font.0, "Play Again", RestartButton);
+                        spawn_button(row, &font.0, "Main Menu", MainMenuButton);
+                    });
+            });
+    }
+}
+
+// Tints the winning cells and draws a bar from the first cell through the
+// last. A line wrapping around a torus gets a bar for each run of neighbouring
+// cells, reaching out to the edge it crosses.
+fn spawn_win_highlight(
+    commands: &mut Commands,
+    line: &WinningLine,
+    layout: &BoardLayout,
+    q_board_cells: &Query<(&BoardPosition, &GlobalTransform)>,
+) {
+    let cell_center = |(row, col): (usize, usize)| {
+        q_board_cells
+            .iter()
+            .find(|(pos, _)| pos.row == row && pos.col == col)
+            .map(|(_, transform)| transform.translation().truncate())
+    };
+
+    for &cell in line.cells.iter() {
+        let Some(center) = cell_center(cell) else {
+            continue;
+        };
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: WIN_HIGHLIGHT_COLOR.with_a(0.4),
+                    custom_size: Some(Vec2::splat(layout.cell_size)),
+                    ..default()
+                },
+                transform: Transform::from_translation(center.extend(50.0)),
+                ..default()
+            },
+            WinHighlight,
+            GameBoard,
+        ));
+    }
+
+    // One cell further along the line, on screen
+    let step = line
+        .kind
+        .direction()
+        .map(|(d_row, d_col)| Vec2::new(d_col as f32, -d_row as f32) * layout.cell_size);
+    let overhang = layout.cell_size * MARKER_SIZE_RATIO / 2.0;
+    let to_edge = step.map_or(0.0, |step| step.length() / 2.0);
+
+    let segments = line.segments();
+    for (index, segment) in segments.iter().enumerate() {
+        let (Some(start), Some(end)) = (
+            cell_center(segment[0]),
+            cell_center(segment[segment.len() - 1]),
+        ) else {
+            error!("❗ Could not find the cells of the winning line");
+            return;
+        };
+
+        // A run of a single cell takes the line's direction
+        let Some(direction) = (end - start)
+            .try_normalize()
+            .or_else(|| step.and_then(Vec2::try_normalize))
+        else {
+            continue;
+        };
+        let before = if index == 0 { overhang } else { to_edge };
+        let after = if index == segments.len() - 1 {
+            overhang
+        } else {
+            to_edge
+        };
+        let start = start - direction * before;
+        let end = end + direction * after;
+
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: WIN_HIGHLIGHT_COLOR,
+                    custom_size: Some(Vec2::new(
+                        start.distance(end),
+                        layout.scaled(WIN_LINE_THICKNESS),
+                    )),
+                    ..default()
+                },
+                transform: Transform {
+                    translation: ((start + end) / 2.0).extend(200.0),
+                    rotation: Quat::from_rotation_z(direction.y.atan2(direction.x)),
+                    ..default()
+                },
+                ..default()
+            },
+            WinHighlight,
+            GameBoard,
+        ));
+    }
+}
+
+// Colors each Ultimate sub-board: won boards take the winner's color, drawn
+// boards turn grey and the boards the next move may go in are highlighted.
+pub fn update_sub_board_overlays(
+    board_state: Res<BoardState>,
+    q_added: Query<(), Added<SubBoardOverlay>>,
+    mut q_overlays: Query<(&SubBoardOverlay, &mut Sprite)>,
+) {
+    if !board_state.is_changed() && q_added.is_empty() {
+        return;
+    }
+    if q_overlays.is_empty() {
+        return;
+    }
+
+    let statuses = sub_board_statuses(&board_state.board);
+    let target = target_sub_board(&board_state.board, board_state.last_move());
+    for (overlay, mut sprite) in q_overlays.iter_mut() {
+        let playable = !board_state.game_over && (target.is_none() || target == Some(overlay.0));
+        sprite.color = match statuses[overlay.0] {
+            SubBoardStatus::Won(Player::X) => X_COLOR.with_a(WON_BOARD_ALPHA),
+            SubBoardStatus::Won(Player::O) => O_COLOR.with_a(WON_BOARD_ALPHA),
+            SubBoardStatus::Won(Player::Triangle) => TRIANGLE_COLOR.with_a(WON_BOARD_ALPHA),
+            SubBoardStatus::Drawn => DRAWN_BOARD_COLOR,
+            SubBoardStatus::Open if playable => LEGAL_BOARD_COLOR,
+            SubBoardStatus::Open => Color::NONE,
+        };
+    }
+}
+
+// Greys out the Notakto boards that have three in a row.
+pub fn update_dead_board_overlays(
+    board_state: Res<BoardState>,
+    q_added: Query<(), Added<DeadBoardOverlay>>,
+    mut q_overlays: Query<(&DeadBoardOverlay, &mut Sprite)>,
+) {
+    if !board_state.is_changed() && q_added.is_empty() {
+        return;
+    }
+
+    for (overlay, mut sprite) in q_overlays.iter_mut() {
+        sprite.color = if notakto::is_dead(&board_state.board, overlay.0) {
+            DRAWN_BOARD_COLOR
+        } else {
+            Color::NONE
+        };
+    }
+}
+
+// Highlights the mark picked up in the sliding variant (or the first cell of a
+// spooky mark in quantum games), and drops the selection once it can no longer
+// be used (after a move, undo or restart).
+pub fn update_selected_mark_highlight(
+    mut commands: Commands,
+    board_state: Res<BoardState>,
+    layout: Res<BoardLayout>,
+    mut selected_mark: ResMut<SelectedMark>,
+    q_highlights: Query<Entity, With<SelectedMarkHighlight>>,
+) {
+    if let Some((row, col)) = selected_mark.0 {
+        let movable = !board_state.game_over && board_state.is_selectable(row, col);
+        if !movable {
+            selected_mark.0 = None;
+        }
+    }
+    if !selected_mark.is_changed() {
+        return;
+    }
+
+    for entity in q_highlights.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if let Some((row, col)) = selected_mark.0 {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: SELECTED_MARK_COLOR,
+                    custom_size: Some(Vec2::splat(layout.cell_size)),
                     ..default()
                 },
+                transform: Transform::from_translation(layout.cell_center(row, col).extend(5.0)),
                 ..default()
             },
-            GameOverMessage,
+            SelectedMarkHighlight,
+            GameBoard,
         ));
     }
 }
 
+// The Swap button, shown while a human may take over the opening move under
+// the swap rule instead of replying to it.
+pub fn handle_swap_button(
+    swap_clicks: Query<&Interaction, (Changed<Interaction>, With<SwapButton>)>,
+    mut q_visibility: Query<&mut Visibility, With<SwapButton>>,
+    board_state: Res<BoardState>,
+    game_mode: Res<CurrentGameMode>,
+    seats: Res<SeatAssignment>,
+    mut player_move_events: EventWriter<PlayerMoveEvent>,
+) {
+    let available =
+        board_state.can_swap() && !seats.is_ai_turn(game_mode.0, board_state.current_player);
+    for mut visibility in q_visibility.iter_mut() {
+        let shown = if available {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != shown {
+            *visibility = shown;
+        }
+    }
+
+    let clicked = swap_clicks
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if available && clicked {
+        let opening = board_state.moves[0];
+        info!("🔁 Swap requested");
+        player_move_events.send(PlayerMoveEvent {
+            position: (opening.row, opening.col),
+            kind: MoveKind::Swap,
+        });
+    }
+}
+
+// Picks the symbol to place in wild games, with the Place X / Place O buttons
+// or the X and O keys, and highlights the current choice.
+pub fn handle_symbol_picker(
+    keys: Res<Input<KeyCode>>,
+    mut selected_symbol: ResMut<SelectedSymbol>,
+    mut q_buttons: Query<(&Interaction, &SymbolButton, &mut BackgroundColor)>,
+) {
+    if keys.just_pressed(KeyCode::X) {
+        selected_symbol.0 = Player::X;
+    } else if keys.just_pressed(KeyCode::O) {
+        selected_symbol.0 = Player::O;
+    }
+    for (interaction, button, _) in q_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            selected_symbol.0 = button.0;
+        }
+    }
+
+    for (interaction, button, mut background) in q_buttons.iter_mut() {
+        *background = if button.0 == selected_symbol.0 {
+            BUTTON_SELECTED_COLOR
+        } else if *interaction == Interaction::None {
+            BUTTON_COLOR
+        } else {
+            BUTTON_HOVER_COLOR
+        }
+        .into();
+    }
+}
+
+// The Gobblet Gobblers reserve tray: picks the size of the next piece to place
+// from the reserve with its buttons or the 1, 2 and 3 keys (clicking the
+// chosen size again clears it), and shows how many pieces of each size the
+// player to move has left.
+pub fn handle_reserve_tray(
+    keys: Res<Input<KeyCode>>,
+    board_state: Res<BoardState>,
+    mut selected_size: ResMut<SelectedSize>,
+    mut selected_mark: ResMut<SelectedMark>,
+    mut q_buttons: Query<(&Interaction, &SizeButton, &mut BackgroundColor, &Children)>,
+    mut q_text: Query<&mut Text>,
+) {
+    if board_state.rules.variant != GameVariant::Gobblet {
+        return;
+    }
+
+    let mut chosen = None;
+    for (key, size) in [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3]
+        .into_iter()
+        .zip(PieceSize::ALL)
+    {
+        if keys.just_pressed(key) {
+            chosen = Some(size);
+        }
+    }
+    for (interaction, button, _, _) in q_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            chosen = Some(button.0);
+        }
+    }
+    if let Some(size) = chosen {
+        selected_size.0 = (selected_size.0 != Some(size)).then_some(size);
+        selected_mark.0 = None;
+    }
+
+    let state = board_state.gobblet();
+    let player = board_state.current_player;
+    if selected_size
+        .0
+        .is_some_and(|size| state.reserve(player, size) == 0)
+    {
+        selected_size.0 = None;
+    }
+
+    for (interaction, button, mut background, children) in q_buttons.iter_mut() {
+        *background = if Some(button.0) == selected_size.0 {
+            BUTTON_SELECTED_COLOR
+        } else if *interaction == Interaction::None {
+            BUTTON_COLOR
+        } else {
+            BUTTON_HOVER_COLOR
+        }
+        .into();
+
+        let label = format!(
+            "{} {} ×{}",
+            player.to_char(),
+            button.0.label(),
+            state.reserve(player, button.0)
+        );
+        for &child in children.iter() {
+            if let Ok(mut text) = q_text.get_mut(child) {
+                if text.sections[0].value != label {
+                    text.sections[0].value = label.clone();
+                }
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn handle_restart_button(
     keys: Res<Input<KeyCode>>,
+    restart_buttons: Query<&Interaction, (Changed<Interaction>, With<RestartButton>)>,
     mut board_state: ResMut<BoardState>,
+    seats: Res<SeatAssignment>,
+    game_mode: Res<CurrentGameMode>,
+    setup: Res<CurrentSetup>,
+    layout: Res<BoardLayout>,
+    mut ai_delay: ResMut<AIDelay>,
+    mut next_state: ResMut<NextState<AppState>>,
     mut commands: Commands,
     game_over_messages: Query<Entity, With<GameOverMessage>>,
     cell_marks: Query<Entity, With<CellMark>>,
-    restart_buttons: Query<Entity, With<RestartButton>>,
+    blocked_cells: Query<Entity, With<BlockedCell>>,
+    win_highlights: Query<Entity, With<WinHighlight>>,
+    q_board_cells: Query<(Entity, &BoardPosition)>,
 ) {
-    if keys.just_pressed(KeyCode::R) {
+    let restart_clicked = restart_buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+
+    if keys.just_pressed(KeyCode::R) || restart_clicked {
         info!("🔄 Restarting game...");
 
-        // Reset board state
-        board_state.board = [[None; 3]; 3];
-        board_state.current_player = Player::X;
-        board_state.game_over = false;
-        board_state.winner = None;
+        // Reset board state, letting the other side open if the series alternates
+        let starting_player =
+            seats.next_starting_player(board_state.rules, board_state.starting_player);
+        let rules = board_state.rules;
+        *board_state = BoardState::new(rules, starting_player);
+        let weaker = seats.weaker_player(game_mode.0, rules, starting_player);
+        board_state.apply_setup(&setup.0, weaker, &mut rand::thread_rng());
+        ai_delay.timer.reset();
 
-        // Clean up UI elements
+        // Clean up UI elements (including the restart / main menu buttons)
         for entity in game_over_messages.iter() {
-            commands.entity(entity).despawn();
+            commands.entity(entity).despawn_recursive();
         }
 
-        // Remove all X and O marks
-        for entity in cell_marks.iter() {
-            commands.entity(entity).despawn();
+        // Remove all X and O marks and blocked cells (recursive so the parent
+        // cell forgets them), then draw the new game's setup
+        for entity in cell_marks.iter().chain(blocked_cells.iter()) {
+            commands.entity(entity).despawn_recursive();
+        }
+        for (cell_entity, pos) in q_board_cells.iter() {
+            spawn_starting_cell(
+                &mut commands,
+                cell_entity,
+                &board_state,
+                (pos.row, pos.col),
+                &layout,
+            );
         }
 
-        // Remove any remaining restart buttons
-        for entity in restart_buttons.iter() {
-            commands.entity(entity).despawn_recursive();
+        // Remove the winning line highlight
+        for entity in win_highlights.iter() {
+            commands.entity(entity).despawn();
         }
 
-        info!("✅ Game restarted - Player X's turn");
+        next_state.set(AppState::Playing);
+        info!(
+            "✅ Game restarted - Player {}'s turn",
+            starting_player.to_char()
+        );
+    }
+}
+
+// Back (during play) and Main Menu (after a game) both return to the menu.
+#[allow(clippy::type_complexity)]
+pub fn handle_back_button(
+    buttons: Query<
+        &Interaction,
+        (
+            Changed<Interaction>,
+            Or<(With<BackButton>, With<MainMenuButton>)>,
+        ),
+    >,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked)
+    {
+        info!("↩️ Returning to main menu");
+        next_state.set(AppState::MainMenu);
     }
 }
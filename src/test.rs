@@ -1,17 +1,38 @@
 #[cfg(test)]
 mod tests {
     use crate::ai_logic::*;
+    use crate::config::HARD_AI_DEPTH;
+    use crate::export::{format_timestamp, to_csv, to_json, ExportData};
+    use crate::gobblet::{self, PieceSize};
+    use crate::gravity;
+    use crate::notakto;
+    use crate::order_chaos;
+    use crate::persistence::SaveData;
+    use crate::quantum;
+    use crate::qubic;
+    use crate::resources::{BoardState, GameStats, SeatAssignment};
+    use crate::search::{self, Game};
+    use crate::three_marks;
+    use crate::three_player;
     use crate::types::*;
+    use crate::ultimate;
+    use crate::wild;
+    use bevy::prelude::default;
 
     // Helper function to create test boards
     fn create_test_board(moves: &[(usize, usize, Player)]) -> Board {
-        let mut board = [[None; 3]; 3];
+        let mut board = Board::default();
         for &(row, col, player) in moves {
             board[row][col] = Some(player);
         }
         board
     }
 
+    // The move the shared search picks in `game`, looking `depth` plies ahead
+    fn search_move<G: Game>(mut game: G, depth: i32) -> Option<G::Move> {
+        search::best_move(&mut game, depth).map(|(game_move, _)| game_move)
+    }
+
     #[test]
     fn test_check_winner_empty_board() {
         let board = create_test_board(&[]);
@@ -58,6 +79,1195 @@ mod tests {
         assert_eq!(check_winner(&board), Some(Player::O));
     }
 
+    #[test]
+    fn test_find_winning_line_reports_cells() {
+        let board = create_test_board(&[(0, 1, Player::X), (1, 1, Player::X), (2, 1, Player::X)]);
+        let line = find_winning_line(&board).expect("a winning line");
+        assert_eq!(line.player, Player::X);
+        assert_eq!(line.kind, LineKind::Column(1));
+        assert_eq!(line.cells, [(0, 1), (1, 1), (2, 1)]);
+
+        let board = create_test_board(&[(0, 2, Player::O), (1, 1, Player::O), (2, 0, Player::O)]);
+        let line = find_winning_line(&board).expect("a winning line");
+        assert_eq!(line.kind, LineKind::AntiDiagonal);
+        assert_eq!(line.cells, [(0, 2), (1, 1), (2, 0)]);
+
+        assert_eq!(find_winning_line(&create_test_board(&[])), None);
+    }
+
+    #[test]
+    fn test_winning_lines_per_board_size() {
+        assert_eq!(winning_lines(BoardConfig::CLASSIC).len(), 8);
+        assert_eq!(winning_lines(BoardConfig::new(4, 4)).len(), 10);
+        // 11 segments in each of 15 rows and 15 columns, 11×11 per diagonal direction
+        assert_eq!(winning_lines(BoardConfig::new(15, 5)).len(), 572);
+        assert!(winning_lines(BoardConfig::new(3, 4)).is_empty());
+    }
+
+    #[test]
+    fn test_torus_lines_wrap_around_the_edges() {
+        let torus = BoardConfig::CLASSIC.with_topology(Topology::Torus);
+        // Three rows, three columns and three broken diagonals each way
+        assert_eq!(winning_lines(torus).len(), 12);
+        assert_eq!(
+            winning_lines(BoardConfig::new(4, 4).with_topology(Topology::Torus)).len(),
+            16
+        );
+        assert_eq!(
+            winning_lines(BoardConfig::new(5, 4).with_topology(Topology::Torus)).len(),
+            100
+        );
+
+        let mut board = Board::new(torus);
+        for (row, col) in [(0, 2), (1, 0), (2, 1)] {
+            board[row][col] = Some(Player::O);
+        }
+        let line = find_winning_line(&board).expect("a wrapped diagonal");
+        assert_eq!(line.kind, LineKind::Diagonal);
+        // The same marks on a flat board make no line
+        let flat = create_test_board(&[(0, 2, Player::O), (1, 0, Player::O), (2, 1, Player::O)]);
+        assert_eq!(check_winner(&flat), None);
+
+        assert_eq!(torus.label(), "3×3, wrapped");
+        assert_eq!(
+            torus.next_preset(),
+            BoardConfig::new(4, 4).with_topology(Topology::Torus)
+        );
+    }
+
+    #[test]
+    fn test_wrapped_line_splits_into_segments_at_the_edge() {
+        let board_config = BoardConfig::new(5, 4).with_topology(Topology::Torus);
+        let mut board = Board::new(board_config);
+        for col in [3, 4, 0, 1] {
+            board[2][col] = Some(Player::X);
+        }
+        let line = find_winning_line(&board).expect("a row across the edge");
+        assert_eq!(line.cells, [(2, 3), (2, 4), (2, 0), (2, 1)]);
+        assert_eq!(
+            line.segments(),
+            [&[(2, 3), (2, 4)][..], &[(2, 0), (2, 1)][..]]
+        );
+
+        // An anti-diagonal that stays on the board is one piece
+        let mut board = Board::new(board_config);
+        for row in 0..4 {
+            board[row][4 - row] = Some(Player::O);
+        }
+        let line = find_winning_line_through(&board, 0, 4).expect("an anti-diagonal");
+        assert_eq!(line.segments(), [&line.cells[..]]);
+
+        // The AI sees moves across the edge on larger boards
+        let mut board = Board::new(BoardConfig::new(15, 5).with_topology(Topology::Torus));
+        for col in [11, 12, 13, 14] {
+            board[7][col] = Some(Player::O);
+        }
+        board[7][10] = Some(Player::X);
+        assert!(candidate_moves(&board).contains(&(7, 0)));
+        assert_eq!(
+            get_best_move_with_depth(&board, Player::O, 1, false),
+            Some((7, 0))
+        );
+    }
+
+    fn swap_rules(variant: GameVariant, board: BoardConfig) -> GameRules {
+        GameRules {
+            variant,
+            board,
+            swap: true,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn test_swap_takes_over_the_opening_move() {
+        let rules = swap_rules(GameVariant::Standard, BoardConfig::new(7, 4));
+        assert_eq!(rules.label(), "Standard 7×7, 4 in a row, swap");
+        let mut state = BoardState::new(rules, Player::X);
+        assert!(!state.can_swap());
+        state.play((3, 3), MoveKind::Place);
+        assert!(state.can_swap());
+
+        let record = state.play((3, 3), MoveKind::Swap);
+        assert_eq!(record.mark(), Player::O);
+        assert_eq!(state.board[3][3], Some(Player::O));
+        assert_eq!(state.current_player, Player::X);
+        assert!(!state.can_swap());
+
+        // Taking the swap back hands the opening mark back to X
+        state.undo();
+        assert_eq!(state.board[3][3], Some(Player::X));
+        assert_eq!(state.current_player, Player::O);
+        state.redo();
+        assert_eq!(state.board[3][3], Some(Player::O));
+
+        // Against the AI, undo takes back both the opening and the swap
+        let seats = SeatAssignment::default();
+        let undone = state.undo_turn(|player| seats.is_ai_turn(GameMode::HumanVsAI, player));
+        assert_eq!(undone.len(), 2);
+        assert!(state.board.is_empty());
+        assert_eq!(state.current_player, Player::X);
+
+        // Variants where a move is more than a mark of one's own ignore the rule
+        let mut state = BoardState::new(
+            swap_rules(GameVariant::Wild, BoardConfig::CLASSIC),
+            Player::X,
+        );
+        state.play((1, 1), MoveKind::Place);
+        assert!(!state.can_swap());
+    }
+
+    #[test]
+    fn test_ai_swaps_only_favourable_openings() {
+        let mut board = Board::new(BoardConfig::new(7, 4));
+        board[3][3] = Some(Player::X);
+        assert!(search::should_swap(
+            StandardGame::new(&board, Player::O, false),
+            Difficulty::Hard
+        ));
+
+        let mut board = Board::new(BoardConfig::new(7, 4));
+        board[0][0] = Some(Player::X);
+        assert!(!search::should_swap(
+            StandardGame::new(&board, Player::O, false),
+            Difficulty::Hard
+        ));
+
+        // Every 3×3 opening is a draw with best play, so none is worth taking
+        let board = create_test_board(&[(1, 1, Player::X)]);
+        assert!(!search::should_swap(
+            StandardGame::new(&board, Player::O, false),
+            Difficulty::Hard
+        ));
+
+        // Under gravity the middle column is the strong opening
+        let mut board = Board::new(BoardConfig::new(7, 4));
+        board[6][3] = Some(Player::X);
+        assert!(search::should_swap(
+            gravity::GravityGame::new(&board, Player::O, false),
+            Difficulty::Hard
+        ));
+        let mut board = Board::new(BoardConfig::new(7, 4));
+        board[6][0] = Some(Player::X);
+        assert!(!search::should_swap(
+            gravity::GravityGame::new(&board, Player::O, false),
+            Difficulty::Hard
+        ));
+    }
+
+    #[test]
+    fn test_blocked_cells_are_out_of_play() {
+        let mut board = create_test_board(&[(0, 0, Player::X), (0, 1, Player::X)]);
+        board.block(0, 2);
+        assert!(board.is_blocked(0, 2));
+        assert!(!board.empty_cells().any(|cell| cell == (0, 2)));
+        // The top row, the right column and the anti-diagonal can no longer be won
+        assert_eq!(board.lines().len(), 5);
+
+        // With the top row dead, the AI has no reason to play near it
+        let best = get_best_move(&board, Player::O).expect("cells are left");
+        assert_ne!(best, (0, 2));
+        assert_eq!(best, (1, 1));
+
+        // Under gravity a blocked cell is a floor, even with room below it
+        let mut board = Board::new(BoardConfig::new(4, 4));
+        board.block(3, 1);
+        assert_eq!(gravity::drop_row(&board, 1), Some(2));
+        board.block(1, 2);
+        assert_eq!(gravity::drop_row(&board, 2), Some(0));
+        board.block(0, 3);
+        assert_eq!(gravity::drop_row(&board, 3), None);
+
+        // A board whose open cells are filled is full
+        let mut board = create_test_board(&[]);
+        for (row, col) in [(0, 0), (1, 1), (2, 2)] {
+            board.block(row, col);
+        }
+        for (row, col) in board.empty_cells().collect::<Vec<_>>() {
+            board[row][col] = Some(Player::X);
+        }
+        assert!(is_board_full(&board));
+        assert_eq!(check_winner(&board), None);
+    }
+
+    #[test]
+    fn test_setup_blocks_cells_and_places_handicap_marks() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let setup = GameSetup {
+            random_blocked: 2,
+            blocked: vec![(1, 1)],
+            handicap: 1,
+        };
+        let mut state = BoardState::new(GameRules::default(), Player::X);
+        state.apply_setup(&setup, Player::O, &mut rng);
+        assert_eq!(state.start.blocked.len(), 3);
+        assert!(state.board.is_blocked(1, 1));
+        assert!(!state.is_legal((1, 1), MoveKind::Place));
+
+        // The handicap mark takes a cell in the most lines and is not a move
+        let [((row, col), Player::O)] = state.start.marks[..] else {
+            panic!("one handicap mark for O: {:?}", state.start.marks);
+        };
+        let lines_through = |(row, col)| state.board.lines_through(row, col).count();
+        assert!(state
+            .board
+            .empty_cells()
+            .all(|cell| lines_through(cell) <= lines_through((row, col))));
+        assert_eq!(state.board[row][col], Some(Player::O));
+        assert!(state.moves.is_empty());
+        assert_eq!(state.current_player, Player::X);
+
+        // At least half the board stays open however many cells are asked for
+        let mut state = BoardState::new(GameRules::default(), Player::X);
+        let setup = GameSetup {
+            random_blocked: 6,
+            ..GameSetup::default()
+        };
+        state.apply_setup(&setup, Player::O, &mut rng);
+        assert_eq!(state.start.blocked.len(), 4);
+
+        // Other variants ignore the setup
+        let mut state = BoardState::new(
+            GameRules {
+                variant: GameVariant::Quantum,
+                ..default()
+            },
+            Player::X,
+        );
+        state.apply_setup(&setup, Player::O, &mut rng);
+        assert_eq!(state.start, StartingPosition::default());
+    }
+
+    #[test]
+    fn test_cells_can_be_blocked_by_hand_until_the_first_move() {
+        let mut state = BoardState::new(GameRules::default(), Player::X);
+        assert_eq!(state.toggle_blocked(1, 1), Some(true));
+        assert!(state.board.is_blocked(1, 1));
+        assert_eq!(state.toggle_blocked(1, 1), Some(false));
+        assert!(!state.board.is_blocked(1, 1));
+        assert_eq!(state.board.lines().len(), 8);
+
+        assert_eq!(state.toggle_blocked(0, 0), Some(true));
+        state.play((2, 2), MoveKind::Place);
+        assert_eq!(state.toggle_blocked(0, 0), None);
+        assert_eq!(
+            state.undo().map(|record| (record.row, record.col)),
+            Some((2, 2))
+        );
+        assert!(state.board.is_blocked(0, 0));
+
+        // Against the AI the human gets the handicap, otherwise whoever moves last
+        let seats = SeatAssignment {
+            human_player: Player::O,
+            ..default()
+        };
+        let rules = GameRules::default();
+        assert_eq!(
+            seats.weaker_player(GameMode::HumanVsAI, rules, Player::O),
+            Player::O
+        );
+        assert_eq!(
+            seats.weaker_player(GameMode::HumanVsHuman, rules, Player::O),
+            Player::X
+        );
+        let three = GameRules {
+            variant: GameVariant::ThreePlayer,
+            ..default()
+        };
+        assert_eq!(
+            seats.weaker_player(GameMode::AIVsAI, three, Player::X),
+            Player::Triangle
+        );
+    }
+
+    #[test]
+    fn test_k_in_a_row_on_larger_board() {
+        let mut board = Board::new(BoardConfig::new(5, 4));
+        for (row, col) in [(1, 0), (2, 1), (3, 2)] {
+            board[row][col] = Some(Player::X);
+        }
+        assert_eq!(check_winner(&board), None, "three in a row is not enough");
+
+        board[4][3] = Some(Player::X);
+        let line = find_winning_line(&board).expect("a winning line");
+        assert_eq!(line.kind, LineKind::Diagonal);
+        assert_eq!(line.cells, [(1, 0), (2, 1), (3, 2), (4, 3)]);
+        assert_eq!(find_winning_line_through(&board, 4, 3), Some(line));
+        assert_eq!(find_winning_line_through(&board, 0, 0), None);
+    }
+
+    #[test]
+    fn test_board_presets_cycle() {
+        let mut config = BoardConfig::CLASSIC;
+        let mut visited = Vec::new();
+        for _ in 0..BoardConfig::PRESETS.len() {
+            visited.push(config);
+            config = config.next_preset();
+        }
+        assert_eq!(visited, BoardConfig::PRESETS);
+        assert_eq!(config, BoardConfig::CLASSIC);
+
+        assert_eq!(BoardConfig::CLASSIC.label(), "3×3");
+        assert_eq!(BoardConfig::new(15, 5).label(), "15×15, 5 in a row");
+    }
+
+    #[test]
+    fn test_ai_on_gomoku_board() {
+        // O completes its own five rather than blocking X's open four
+        let mut board = Board::new(BoardConfig::new(15, 5));
+        for col in 3..7 {
+            board[7][col] = Some(Player::O);
+            board[9][col + 1] = Some(Player::X);
+        }
+        board[7][7] = Some(Player::X);
+        assert_eq!(get_best_move(&board, Player::O), Some((7, 2)));
+
+        // X's four is closed on one side, so O must take the other end
+        let mut board = Board::new(BoardConfig::new(15, 5));
+        for col in 3..7 {
+            board[7][col] = Some(Player::X);
+        }
+        board[7][7] = Some(Player::O);
+        board[8][4] = Some(Player::O);
+        board[8][5] = Some(Player::O);
+        assert_eq!(get_best_move(&board, Player::O), Some((7, 2)));
+    }
+
+    #[test]
+    fn test_misere_reverses_line_winner() {
+        let misere = GameRules {
+            misere: true,
+            ..default()
+        };
+        assert_eq!(misere.line_winner(Player::X), Player::O);
+        assert_eq!(GameRules::default().line_winner(Player::X), Player::X);
+        assert_eq!(misere.label(), "Standard 3×3, misère");
+
+        // A line is always Order's, so misère does not apply
+        let order_and_chaos = GameRules {
+            variant: GameVariant::OrderAndChaos,
+            ..misere
+        };
+        assert_eq!(order_and_chaos.line_winner(Player::X), Player::X);
+        assert!(!order_and_chaos.label().contains("misère"));
+    }
+
+    #[test]
+    fn test_misere_ai_avoids_completing_a_line() {
+        let board = create_test_board(&[
+            (0, 0, Player::O),
+            (0, 1, Player::O),
+            (1, 1, Player::X),
+            (2, 2, Player::X),
+        ]);
+        let ai_move = get_best_move_with_depth(&board, Player::O, HARD_AI_DEPTH, true);
+        assert!(ai_move.is_some());
+        assert_ne!(ai_move, Some((0, 2)), "O must not complete its own line");
+    }
+
+    #[test]
+    fn test_misere_perfect_play_is_a_draw() {
+        let mut board = Board::default();
+        let mut player = Player::X;
+        while crate::types::get_game_result(&board) == GameResult::InProgress {
+            let (row, col) = get_best_move_with_depth(&board, player, HARD_AI_DEPTH, true)
+                .expect("a move on a board in progress");
+            board[row][col] = Some(player);
+            player = player.opposite();
+        }
+        assert_eq!(crate::types::get_game_result(&board), GameResult::Draw);
+    }
+
+    fn ultimate_rules() -> GameRules {
+        GameRules {
+            variant: GameVariant::Ultimate,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn test_ultimate_sends_opponent_to_sub_board() {
+        let mut state = BoardState::new(ultimate_rules(), Player::X);
+        assert_eq!(ultimate::legal_moves(&state.board, None).len(), 81);
+
+        // Centre cell of the top-left sub-board sends O to the centre sub-board
+        state.play((1, 1), MoveKind::Place);
+        assert_eq!(
+            ultimate::target_sub_board(&state.board, state.last_move()),
+            Some(4)
+        );
+        assert!(state.is_legal((4, 4), MoveKind::Place));
+        assert!(!state.is_legal((0, 2), MoveKind::Place));
+
+        state.undo();
+        assert!(
+            state.is_legal((0, 2), MoveKind::Place),
+            "undo restores the free choice"
+        );
+    }
+
+    #[test]
+    fn test_ultimate_decided_sub_board_frees_the_choice() {
+        let mut board = Board::new(ultimate::ULTIMATE_BOARD);
+        // X wins the top-left sub-board
+        for col in 0..3 {
+            board[0][col] = Some(Player::X);
+        }
+        assert_eq!(
+            ultimate::sub_board_status(&board, 0),
+            ultimate::SubBoardStatus::Won(Player::X)
+        );
+
+        // A move in the top-left cell of any sub-board points at the decided one
+        board[3][3] = Some(Player::O);
+        let last_move = Some((3, 3));
+        assert_eq!(ultimate::target_sub_board(&board, last_move), None);
+        assert!(ultimate::is_legal_move(&board, last_move, 8, 8));
+        assert!(!ultimate::is_legal_move(&board, last_move, 1, 1));
+        assert_eq!(
+            ultimate::legal_moves(&board, last_move).len(),
+            81 - 9 - 1,
+            "every empty cell outside the won sub-board"
+        );
+    }
+
+    #[test]
+    fn test_ultimate_meta_board_win() {
+        let mut board = Board::new(ultimate::ULTIMATE_BOARD);
+        // X takes the top row of sub-boards by winning each one's middle column
+        for sub_col in 0..3 {
+            for row in 0..3 {
+                board[row][sub_col * 3 + 1] = Some(Player::X);
+            }
+        }
+
+        assert_eq!(
+            ultimate::game_result(&board, Some((2, 7))),
+            GameResult::Win(Player::X)
+        );
+        let line = ultimate::find_winning_line(&board).expect("a meta line");
+        assert_eq!(line.kind, LineKind::Row(0));
+        assert_eq!(line.cells, [(1, 1), (1, 4), (1, 7)]);
+        assert!(ultimate::legal_moves(&board, Some((2, 7))).is_empty());
+    }
+
+    #[test]
+    fn test_ultimate_ai_completes_meta_line() {
+        let mut board = Board::new(ultimate::ULTIMATE_BOARD);
+        // X has won the top-left and top-middle sub-boards
+        for col in 0..3 {
+            board[0][col] = Some(Player::X);
+            board[0][col + 3] = Some(Player::X);
+        }
+        // ...and threatens the top-right one, where O's last move sends X
+        board[1][6] = Some(Player::X);
+        board[2][6] = Some(Player::X);
+        board[3][5] = Some(Player::O);
+
+        let ai_move = search_move(
+            ultimate::UltimateGame::new(&board, Some((3, 5)), Player::X, false),
+            3,
+        );
+        assert_eq!(ai_move, Some((0, 6)));
+    }
+
+    #[test]
+    fn test_gravity_marks_land_at_the_bottom() {
+        let rules = GameRules {
+            variant: GameVariant::Gravity,
+            board: BoardConfig::new(7, 4),
+            ..default()
+        };
+        let mut state = BoardState::new(rules, Player::X);
+        assert_eq!(state.landing_cell(0, 3), Some((6, 3)));
+        assert!(!state.is_legal((0, 3), MoveKind::Place));
+
+        state.play((6, 3), MoveKind::Place);
+        assert_eq!(state.landing_cell(2, 3), Some((5, 3)));
+        for row in (0..6).rev() {
+            state.play((row, 3), MoveKind::Place);
+        }
+        assert_eq!(state.landing_cell(0, 3), None);
+        assert_eq!(gravity::legal_moves(&state.board).len(), 6);
+    }
+
+    #[test]
+    fn test_gravity_ai_blocks_bottom_row_threat() {
+        let mut board = Board::new(BoardConfig::new(7, 4));
+        // X has three in a row along the bottom, open on one side only
+        for col in 0..3 {
+            board[6][col] = Some(Player::X);
+        }
+        board[5][0] = Some(Player::O);
+        board[5][1] = Some(Player::O);
+
+        let ai_move = search_move(gravity::GravityGame::new(&board, Player::O, false), 4);
+        assert_eq!(ai_move, Some((6, 3)));
+    }
+
+    #[test]
+    fn test_gravity_with_a_block_ends_in_a_draw() {
+        let rules = GameRules {
+            variant: GameVariant::Gravity,
+            ..default()
+        };
+        let mut state = BoardState::new(rules, Player::X);
+        // The cell under the block can never be reached
+        state.board.block(1, 1);
+        for cell in [(2, 0), (2, 2), (1, 2), (1, 0), (0, 0), (0, 2), (0, 1)] {
+            assert!(state.is_legal(cell, MoveKind::Place));
+            state.play(cell, MoveKind::Place);
+        }
+        assert_eq!(state.board[2][1], None);
+        assert_eq!(state.winning_line(), None);
+        assert!(gravity::legal_moves(&state.board).is_empty());
+        assert!(state.is_draw());
+
+        let game = gravity::GravityGame::new(&state.board, Player::O, false);
+        assert_eq!(game.outcome(), Some(search::Outcome::Draw));
+    }
+
+    fn three_marks_state(variant: GameVariant, moves: &[(usize, usize)]) -> BoardState {
+        let rules = GameRules {
+            variant,
+            ..default()
+        };
+        let mut state = BoardState::new(rules, Player::X);
+        for &(row, col) in moves {
+            state.play((row, col), MoveKind::Place);
+        }
+        state
+    }
+
+    #[test]
+    fn test_disappearing_removes_oldest_mark() {
+        // X: (0,0) (2,2) (0,2), O: (1,0) (2,1) (1,2)
+        let mut state = three_marks_state(
+            GameVariant::Disappearing,
+            &[(0, 0), (1, 0), (2, 2), (2, 1), (0, 2), (1, 2)],
+        );
+
+        let record = state.play((2, 0), MoveKind::Place);
+        assert_eq!(record.kind, MoveKind::Slide { from: (0, 0) });
+        assert_eq!(state.board[0][0], None);
+        assert_eq!(state.board[2][0], Some(Player::X));
+
+        state.undo();
+        assert_eq!(state.board[0][0], Some(Player::X));
+        assert_eq!(state.board[2][0], None);
+        state.redo();
+        assert_eq!(state.board[0][0], None);
+    }
+
+    #[test]
+    fn test_sliding_requires_moving_a_mark() {
+        let mut state = three_marks_state(
+            GameVariant::Sliding,
+            &[(0, 0), (1, 0), (2, 2), (2, 1), (0, 2), (1, 2)],
+        );
+        assert!(state.must_move_mark());
+        assert!(!state.is_legal((1, 1), MoveKind::Place));
+        assert!(
+            !state.is_legal((1, 1), MoveKind::Slide { from: (1, 0) }),
+            "not X's mark"
+        );
+        assert!(state.is_legal((1, 1), MoveKind::Slide { from: (0, 0) }));
+
+        // The moved mark leaves its old cell
+        state.play((0, 1), MoveKind::Slide { from: (0, 0) });
+        assert_eq!(state.board[0][0], None);
+        assert_eq!(state.winning_line(), None);
+        state.play((0, 0), MoveKind::Slide { from: (2, 1) });
+        state.play((2, 1), MoveKind::Slide { from: (2, 2) });
+        assert_eq!(state.board[2][1], Some(Player::X));
+    }
+
+    #[test]
+    fn test_sliding_repetition_is_a_draw() {
+        let mut state = three_marks_state(
+            GameVariant::Sliding,
+            &[(0, 0), (0, 1), (1, 1), (2, 1), (2, 0), (1, 2)],
+        );
+        // Both sides shuttle one mark back and forth
+        for _ in 0..2 {
+            assert!(!state.is_draw());
+            state.play((0, 2), MoveKind::Slide { from: (0, 0) });
+            state.play((2, 2), MoveKind::Slide { from: (1, 2) });
+            state.play((0, 0), MoveKind::Slide { from: (0, 2) });
+            state.play((1, 2), MoveKind::Slide { from: (2, 2) });
+        }
+        assert!(state.is_draw());
+    }
+
+    #[test]
+    fn test_sliding_ai_completes_line_by_moving() {
+        let state = three_marks_state(
+            GameVariant::Sliding,
+            &[(0, 0), (1, 0), (0, 1), (1, 1), (2, 2), (2, 0)],
+        );
+        // X can slide (2,2) up to (0,2) to complete the top row
+        let game = three_marks::ThreeMarksGame::new(
+            GameVariant::Sliding,
+            &state.board,
+            &state.moves,
+            Player::X,
+            false,
+        );
+        let ai_move =
+            search_move(game, crate::config::THREE_MARKS_HARD_AI_DEPTH).expect("X has moves");
+        assert_eq!(ai_move.to, (0, 2));
+        assert_eq!(ai_move.from, Some((2, 2)));
+    }
+
+    #[test]
+    fn test_wild_line_is_credited_to_the_mover() {
+        let rules = GameRules {
+            variant: GameVariant::Wild,
+            ..default()
+        };
+        let mut state = BoardState::new(rules, Player::X);
+        state.play((0, 0), MoveKind::Wild { symbol: Player::X });
+        state.play((2, 2), MoveKind::Wild { symbol: Player::O });
+        state.play((0, 1), MoveKind::Wild { symbol: Player::X });
+        assert_eq!(state.winning_line(), None);
+
+        // O completes the row of Xs and wins with it
+        let record = state.play((0, 2), MoveKind::Wild { symbol: Player::X });
+        assert_eq!(record.player, Player::O);
+        assert_eq!(record.mark(), Player::X);
+        assert_eq!(state.board[0][2], Some(Player::X));
+        let line = state.winning_line().expect("a row of Xs");
+        assert_eq!(line.player, Player::O);
+    }
+
+    #[test]
+    fn test_wild_ai_plays_either_symbol() {
+        let board = create_test_board(&[
+            (0, 0, Player::O),
+            (0, 1, Player::O),
+            (1, 0, Player::X),
+            (2, 2, Player::X),
+        ]);
+        // X wins by finishing the row of Os
+        let ai_move = search_move(wild::WildGame::new(&board, Player::X, false), 3);
+        assert_eq!(ai_move, Some(((0, 2), Player::O)));
+
+        // Under misère rules finishing it would lose
+        let ai_move = search_move(wild::WildGame::new(&board, Player::X, true), 3);
+        assert_ne!(ai_move, Some(((0, 2), Player::O)));
+    }
+
+    #[test]
+    fn test_notakto_last_board_to_die_loses() {
+        let rules = GameRules {
+            variant: GameVariant::Notakto,
+            notakto_boards: 2,
+            ..default()
+        };
+        let mut state = BoardState::new(rules, Player::X);
+        // Both players fill the top row of board 0, killing it
+        for col in 0..3 {
+            let record = state.play((0, col), MoveKind::Place);
+            assert_eq!(record.mark(), Player::X);
+        }
+        assert!(notakto::is_dead(&state.board, 0));
+        assert!(!state.is_legal((1, 1), MoveKind::Place), "board 0 is dead");
+        assert!(state.is_legal((1, 4), MoveKind::Place));
+        assert_eq!(state.winning_line(), None);
+
+        // O kills the last board and loses
+        for col in 3..6 {
+            state.play((2, col), MoveKind::Place);
+        }
+        let line = state.winning_line().expect("every board is dead");
+        assert_eq!(line.player, Player::O);
+        assert_eq!(rules.line_winner(line.player), Player::X);
+    }
+
+    #[test]
+    fn test_notakto_quotient_values() {
+        let mut board = notakto::new_board(1);
+        // One empty board is a first-player win, by playing in the centre
+        assert!(!notakto::position_value(&board).is_p_position());
+        assert_eq!(notakto::get_best_move(&board), Some((1, 1)));
+        board[1][1] = Some(Player::X);
+        assert!(notakto::position_value(&board).is_p_position());
+
+        // Two empty boards are a second-player win, three a first-player win
+        assert!(notakto::position_value(&notakto::new_board(2)).is_p_position());
+        assert!(!notakto::position_value(&notakto::new_board(3)).is_p_position());
+    }
+
+    #[test]
+    fn test_notakto_ai_always_wins_from_a_won_position() {
+        use rand::rngs::StdRng;
+        use rand::seq::SliceRandom;
+        use rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for boards in 1..=notakto::MAX_BOARDS {
+            for _ in 0..10 {
+                let mut board = notakto::new_board(boards);
+                // The AI takes whichever side wins from the start
+                let mut ai_to_move = !notakto::position_value(&board).is_p_position();
+                while !notakto::all_dead(&board) {
+                    let (row, col) = if ai_to_move {
+                        notakto::get_best_move(&board).expect("a live board")
+                    } else {
+                        *notakto::legal_moves(&board).choose(&mut rng).unwrap()
+                    };
+                    board[row][col] = Some(Player::X);
+                    ai_to_move = !ai_to_move;
+                }
+                assert!(ai_to_move, "the opponent killed the last board");
+            }
+        }
+    }
+
+    #[test]
+    fn test_quantum_cycle_collapses_both_marks() {
+        let rules = GameRules {
+            variant: GameVariant::Quantum,
+            ..default()
+        };
+        let mut state = BoardState::new(rules, Player::X);
+        state.play((0, 0), MoveKind::Quantum { partner: (0, 1) });
+        assert_eq!(state.current_player, Player::O);
+        assert!(state.quantum().pending_collapse().is_none());
+
+        // O's mark on the same two cells closes a cycle, and X collapses it
+        state.play((0, 0), MoveKind::Quantum { partner: (0, 1) });
+        assert_eq!(state.current_player, Player::X);
+        assert!(state.quantum().pending_collapse().is_some());
+        assert!(!state.is_legal((1, 1), MoveKind::Quantum { partner: (2, 2) }));
+        assert!(!state.is_legal((2, 2), MoveKind::Place));
+
+        // Sending O's mark to (0, 0) forces X's into (0, 1); X then moves again
+        state.play((0, 0), MoveKind::Place);
+        assert_eq!(state.board[0][0], Some(Player::O));
+        assert_eq!(state.board[0][1], Some(Player::X));
+        assert_eq!(state.current_player, Player::X);
+        assert!(state.quantum().spooky_marks().is_empty());
+
+        state.undo();
+        assert_eq!(state.board[0][0], None);
+        assert_eq!(state.current_player, Player::X);
+        assert!(state.quantum().pending_collapse().is_some());
+    }
+
+    // X and O entangle the top two rows in one cycle, closed by O's sixth mark
+    fn quantum_split_position() -> BoardState {
+        let rules = GameRules {
+            variant: GameVariant::Quantum,
+            ..default()
+        };
+        let mut state = BoardState::new(rules, Player::X);
+        for (cell, partner) in [
+            ((0, 0), (1, 0)),
+            ((1, 0), (0, 1)),
+            ((0, 1), (1, 1)),
+            ((1, 1), (0, 2)),
+            ((0, 2), (1, 2)),
+            ((1, 2), (0, 0)),
+        ] {
+            state.play((cell.0, cell.1), MoveKind::Quantum { partner });
+        }
+        state
+    }
+
+    #[test]
+    fn test_quantum_simultaneous_lines_favour_the_older() {
+        let mut state = quantum_split_position();
+        assert_eq!(state.winning_line(), None);
+        assert!(state.quantum().pending_collapse().is_some());
+
+        // O's sixth mark goes to (1, 2): X gets row 0 with marks 1, 3, 5 and
+        // O row 1 with marks 2, 4, 6, so X's line is the older one
+        state.play((1, 2), MoveKind::Place);
+        let win = state.quantum().result().expect("both rows are complete");
+        assert_eq!(win.line.player, Player::X);
+        assert_eq!(win.line.cells, vec![(0, 0), (0, 1), (0, 2)]);
+        assert!(win.split);
+        assert_eq!(state.winning_line(), Some(win.line));
+        assert!(state.is_split_win());
+        assert_eq!(GameResult::SplitWin(Player::X).label(), "X wins 1–½");
+    }
+
+    #[test]
+    fn test_quantum_ai_collapses_into_a_win() {
+        let state = quantum_split_position();
+        let quantum_move = search_move(quantum::QuantumGame::new(state.quantum(), false), 2)
+            .expect("a collapse is pending");
+        assert_eq!(quantum_move.partner, None);
+
+        let mut next = state.quantum().clone();
+        next.apply(quantum_move);
+        assert_eq!(next.result().map(|win| win.line.player), Some(Player::X));
+    }
+
+    #[test]
+    fn test_quantum_undo_restores_the_spooky_marks() {
+        let mut state = quantum_split_position();
+        state.play((1, 2), MoveKind::Place);
+        assert!(state.quantum().spooky_marks().is_empty());
+
+        state.undo();
+        assert!(state.quantum().pending_collapse().is_some());
+        assert_eq!(state.quantum().spooky_marks().len(), 6);
+        assert_eq!(state.board, Board::default());
+    }
+
+    #[test]
+    fn test_order_and_chaos_line_wins_for_order_whoever_completes_it() {
+        let rules = GameRules {
+            variant: GameVariant::OrderAndChaos,
+            ..default()
+        };
+        // O opens, so O plays Order
+        let mut state = BoardState::new(rules, Player::O);
+        assert_eq!(state.role_of(Player::O), Some(Role::Order));
+        assert_eq!(state.role_of(Player::X), Some(Role::Chaos));
+
+        for col in 0..4 {
+            state.play((0, col), MoveKind::Wild { symbol: Player::X });
+        }
+        state.play((5, 5), MoveKind::Wild { symbol: Player::O });
+        assert_eq!(state.winning_line(), None);
+
+        // Chaos is forced to complete five Xs, and Order wins
+        let record = state.play((0, 4), MoveKind::Wild { symbol: Player::X });
+        assert_eq!(record.player, Player::X);
+        let line = state.winning_line().expect("five Xs in a row");
+        assert_eq!(rules.line_winner(line.player), Player::O);
+        assert_eq!(
+            GameResult::RoleWin(Role::Order, Player::O).label(),
+            "Order (O) wins"
+        );
+    }
+
+    #[test]
+    fn test_order_and_chaos_full_board_wins_for_chaos() {
+        let rules = GameRules {
+            variant: GameVariant::OrderAndChaos,
+            ..default()
+        };
+        let mut state = BoardState::new(rules, Player::X);
+        // Pairs of each symbol, shifted every row, leave no five in a row
+        for row in 0..6 {
+            for col in 0..6 {
+                let symbol = if (col / 2 + row) % 2 == 0 {
+                    Player::X
+                } else {
+                    Player::O
+                };
+                state.board[row][col] = Some(symbol);
+            }
+        }
+        assert_eq!(state.winning_line(), None);
+        assert!(!state.is_draw());
+        assert_eq!(state.winner_without_line(), Some(Player::O));
+    }
+
+    #[test]
+    fn test_order_and_chaos_ai_roles() {
+        let mut board = Board::new(order_chaos::ORDER_AND_CHAOS_BOARD);
+        for col in 0..4 {
+            board[0][col] = Some(Player::X);
+        }
+        board[0][5] = Some(Player::O);
+
+        // Order completes the five; Chaos spoils it with the other symbol
+        assert_eq!(
+            search_move(order_chaos::OrderChaosGame::new(&board, Role::Order), 2),
+            Some(((0, 4), Player::X))
+        );
+        assert_eq!(
+            search_move(order_chaos::OrderChaosGame::new(&board, Role::Chaos), 2),
+            Some(((0, 4), Player::O))
+        );
+    }
+
+    fn gobblet_state() -> BoardState {
+        let rules = GameRules {
+            variant: GameVariant::Gobblet,
+            ..default()
+        };
+        BoardState::new(rules, Player::X)
+    }
+
+    #[test]
+    fn test_gobblet_larger_pieces_cover_smaller_ones() {
+        let mut state = gobblet_state();
+        state.play(
+            (0, 0),
+            MoveKind::Gobblet {
+                size: PieceSize::Small,
+                from: None,
+            },
+        );
+        assert_eq!(
+            state.gobblet().default_size(None),
+            Some(PieceSize::Large),
+            "largest piece by default"
+        );
+        state.play(
+            (0, 0),
+            MoveKind::Gobblet {
+                size: PieceSize::Large,
+                from: None,
+            },
+        );
+        assert_eq!(state.board[0][0], Some(Player::O));
+        assert_eq!(state.gobblet().reserve(Player::O, PieceSize::Large), 1);
+
+        // Nothing covers a large piece
+        assert!(!state.is_legal(
+            (0, 0),
+            MoveKind::Gobblet {
+                size: PieceSize::Large,
+                from: None
+            }
+        ));
+        assert!(state.is_legal(
+            (1, 1),
+            MoveKind::Gobblet {
+                size: PieceSize::Small,
+                from: None
+            }
+        ));
+
+        state.undo();
+        assert_eq!(state.board[0][0], Some(Player::X));
+        assert_eq!(state.gobblet().reserve(Player::O, PieceSize::Large), 2);
+        assert!(state.is_legal(
+            (0, 0),
+            MoveKind::Gobblet {
+                size: PieceSize::Medium,
+                from: None
+            }
+        ));
+    }
+
+    // O's large piece covers X's small one in the corner, while X builds the
+    // rest of the top row and O two cells of the bottom row
+    fn gobblet_uncovering_position() -> BoardState {
+        let mut state = gobblet_state();
+        for (size, cell) in [
+            (PieceSize::Small, (0, 0)),
+            (PieceSize::Large, (0, 0)),
+            (PieceSize::Small, (0, 1)),
+            (PieceSize::Medium, (2, 0)),
+            (PieceSize::Medium, (0, 2)),
+            (PieceSize::Medium, (2, 1)),
+            (PieceSize::Large, (1, 2)),
+        ] {
+            state.play((cell.0, cell.1), MoveKind::Gobblet { size, from: None });
+        }
+        assert_eq!(state.winning_line(), None);
+        state
+    }
+
+    #[test]
+    fn test_gobblet_uncovered_line_wins_for_its_owner() {
+        let mut state = gobblet_uncovering_position();
+        // Moving the large piece completes O's bottom row but uncovers X's top row
+        assert!(state.is_legal(
+            (2, 2),
+            MoveKind::Gobblet {
+                size: PieceSize::Large,
+                from: Some((0, 0))
+            }
+        ));
+        state.play(
+            (2, 2),
+            MoveKind::Gobblet {
+                size: PieceSize::Large,
+                from: Some((0, 0)),
+            },
+        );
+        assert_eq!(state.board[0][0], Some(Player::X));
+        let line = state.winning_line().expect("both rows are complete");
+        assert_eq!(line.player, Player::X);
+        assert_eq!(line.cells, vec![(0, 0), (0, 1), (0, 2)]);
+
+        // Taking it back puts the large piece back on the corner
+        state.undo();
+        assert_eq!(state.board[0][0], Some(Player::O));
+        assert_eq!(state.board[2][2], None);
+        assert_eq!(state.winning_line(), None);
+    }
+
+    #[test]
+    fn test_gobblet_ai_wins_from_reserve() {
+        let state = gobblet_uncovering_position();
+        let gobblet_move =
+            search_move(gobblet::GobbletGame::new(state.gobblet(), false), 2).expect("O has moves");
+        assert_eq!(gobblet_move.from, None, "lifting the large piece loses");
+
+        let mut next = state.gobblet().clone();
+        next.apply(gobblet_move);
+        assert_eq!(next.winning_line().map(|line| line.player), Some(Player::O));
+    }
+
+    fn three_player_rules(turn_order: TurnOrder) -> GameRules {
+        GameRules {
+            variant: GameVariant::ThreePlayer,
+            board: BoardConfig::new(4, 4),
+            turn_order,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn test_three_player_turns_follow_turn_order() {
+        let mut state = BoardState::new(three_player_rules(TurnOrder::XTriangleO), Player::O);
+        let mut movers = Vec::new();
+        for col in 0..4 {
+            movers.push(state.play((0, col), MoveKind::Place).player);
+        }
+        assert_eq!(
+            movers,
+            [Player::O, Player::X, Player::Triangle, Player::O],
+            "O opens, then X, Δ and O again"
+        );
+
+        state.undo();
+        assert_eq!(state.current_player, Player::O);
+
+        let rules = three_player_rules(TurnOrder::XOTriangle);
+        assert_eq!(rules.players(), [Player::X, Player::O, Player::Triangle]);
+        let alternating = SeatAssignment {
+            alternate_first: true,
+            ..default()
+        };
+        assert_eq!(
+            alternating.next_starting_player(rules, Player::O),
+            Player::Triangle
+        );
+
+        // Three players need at least a 4×4 board
+        let menu_rules = GameRules {
+            board: BoardConfig::new(15, 5),
+            ..rules
+        };
+        assert_eq!(menu_rules.next_board(), BoardConfig::new(4, 4));
+    }
+
+    #[test]
+    fn test_three_player_line_wins_for_triangle() {
+        let rules = GameRules {
+            misere: true,
+            ..three_player_rules(TurnOrder::XOTriangle)
+        };
+        let mut state = BoardState::new(rules, Player::X);
+        // X and O scatter their marks while Δ fills the third row
+        for (row, col) in [
+            (0, 0),
+            (1, 1),
+            (2, 0),
+            (0, 3),
+            (1, 2),
+            (2, 1),
+            (3, 0),
+            (3, 3),
+            (2, 2),
+            (0, 1),
+            (1, 0),
+        ] {
+            state.play((row, col), MoveKind::Place);
+            assert_eq!(state.winning_line(), None);
+        }
+        state.play((2, 3), MoveKind::Place);
+
+        let line = state.winning_line().expect("Δ completed a row");
+        assert_eq!(line.player, Player::Triangle);
+        assert_eq!(line.kind, LineKind::Row(2));
+        // Misère does not apply with three players
+        assert_eq!(state.rules.line_winner(line.player), Player::Triangle);
+    }
+
+    #[test]
+    fn test_three_player_ai_blocks_the_next_player() {
+        let mut board = Board::new(BoardConfig::new(4, 4));
+        for (row, col, player) in [
+            (0, 0, Player::O),
+            (0, 1, Player::O),
+            (0, 2, Player::O),
+            (3, 0, Player::Triangle),
+            (3, 3, Player::Triangle),
+            (2, 1, Player::X),
+        ] {
+            board[row][col] = Some(player);
+        }
+        // O moves right after X and would complete the top row
+        assert_eq!(
+            search_move(
+                three_player::ThreePlayerGame::new(&board, Player::X, TurnOrder::XOTriangle),
+                3
+            ),
+            Some((0, 3))
+        );
+        // Δ completes its own line rather than block anyone
+        board[3][1] = Some(Player::Triangle);
+        assert_eq!(
+            search_move(
+                three_player::ThreePlayerGame::new(&board, Player::Triangle, TurnOrder::XOTriangle),
+                3
+            ),
+            Some((3, 2))
+        );
+    }
+
+    #[test]
+    fn test_qubic_has_76_lines() {
+        let lines = qubic::winning_lines();
+        assert_eq!(lines.len(), 76);
+        let cross_layer = lines
+            .iter()
+            .filter(|line| line.kind == LineKind::CrossLayer)
+            .count();
+        // 16 pillars, 4 × 2 diagonals in each vertical direction, 4 space diagonals
+        assert_eq!(cross_layer, 16 + 16 + 4);
+    }
+
+    #[test]
+    fn test_qubic_space_diagonal_wins() {
+        let mut board = qubic::new_board();
+        assert_eq!(board.dimensions(), (4, 16));
+        for i in 0..3 {
+            let (row, col) = qubic::board_cell(i, i, i);
+            board[row][col] = Some(Player::O);
+        }
+        assert_eq!(check_winner(&board), None);
+
+        let (row, col) = qubic::board_cell(3, 3, 3);
+        board[row][col] = Some(Player::O);
+        let line = find_winning_line(&board).expect("space diagonal is a line");
+        assert_eq!(line.player, Player::O);
+        assert_eq!(line.kind, LineKind::CrossLayer);
+        assert_eq!(line.cells, vec![(0, 0), (1, 5), (2, 10), (3, 15)]);
+    }
+
+    #[test]
+    fn test_qubic_ai_blocks_cross_layer_threat() {
+        let mut board = qubic::new_board();
+        // X holds three cells of the pillar through (row 1, col 2) of every layer
+        for layer in 0..3 {
+            let (row, col) = qubic::board_cell(layer, 1, 2);
+            board[row][col] = Some(Player::X);
+        }
+        for (layer, row, col) in [(0, 0, 0), (2, 3, 3)] {
+            let (row, col) = qubic::board_cell(layer, row, col);
+            board[row][col] = Some(Player::O);
+        }
+
+        let ai_move = search_move(qubic::QubicGame::new(&board, Player::O, false), 2);
+        assert_eq!(ai_move, Some(qubic::board_cell(3, 1, 2)));
+    }
+
     #[test]
     fn test_is_board_full_empty() {
         let board = create_test_board(&[]);
@@ -75,10 +1285,10 @@ mod tests {
 
     #[test]
     fn test_is_board_full_complete() {
-        let mut board: Board = [[None; 3]; 3];
-        for (row, board_row) in board.iter_mut().enumerate() {
-            for (col, cell) in board_row.iter_mut().enumerate() {
-                *cell = Some(if (row + col) % 2 == 0 {
+        let mut board = Board::default();
+        for row in 0..3 {
+            for col in 0..3 {
+                board[row][col] = Some(if (row + col) % 2 == 0 {
                     Player::X
                 } else {
                     Player::O
@@ -117,4 +1327,362 @@ mod tests {
         let ai_move = get_best_move(&board, Player::O);
         assert_eq!(ai_move, Some((0, 2)), "AI should take the winning move");
     }
+
+    // Plays `games` games of a fixed X opponent (half random, half perfect moves)
+    // against the AI as O and returns (ai_wins, ai_losses, draws).
+    fn play_against_fixed_opponent(difficulty: Difficulty, games: u32) -> (u32, u32, u32) {
+        use rand::rngs::StdRng;
+        use rand::seq::SliceRandom;
+        use rand::{Rng, SeedableRng};
+
+        let mut opponent_rng = StdRng::seed_from_u64(42);
+        let mut ai_rng = StdRng::seed_from_u64(7);
+        let (mut wins, mut losses, mut draws) = (0, 0, 0);
+
+        for _ in 0..games {
+            let mut board = Board::default();
+            let mut player = Player::X;
+
+            loop {
+                match crate::types::get_game_result(&board) {
+                    GameResult::Win(Player::O) => wins += 1,
+                    GameResult::Win(Player::X) => losses += 1,
+                    GameResult::Draw => draws += 1,
+                    GameResult::RoleWin(..) => unreachable!("only Order and Chaos has roles"),
+                    GameResult::SplitWin(_) => unreachable!("only quantum games split"),
+                    GameResult::Win(Player::Triangle) => unreachable!("a two-player game"),
+                    GameResult::InProgress => {
+                        let next_move = if player == Player::X {
+                            if opponent_rng.gen_bool(0.5) {
+                                find_empty_cells(&board)
+                                    .choose(&mut opponent_rng)
+                                    .map(|cell| (cell.row, cell.col))
+                            } else {
+                                get_best_move(&board, Player::X)
+                            }
+                        } else {
+                            search::choose_move(
+                                StandardGame::new(&board, Player::O, false),
+                                difficulty,
+                                &mut ai_rng,
+                            )
+                        };
+                        let (row, col) = next_move.expect("a move on a board in progress");
+                        board[row][col] = Some(player);
+                        player = player.opposite();
+                        continue;
+                    }
+                }
+                break;
+            }
+        }
+
+        (wins, losses, draws)
+    }
+
+    #[test]
+    fn test_difficulty_levels_play_differently() {
+        let (easy_wins, easy_losses, _) = play_against_fixed_opponent(Difficulty::Easy, 200);
+        let (_, medium_losses, _) = play_against_fixed_opponent(Difficulty::Medium, 200);
+        let (hard_wins, hard_losses, _) = play_against_fixed_opponent(Difficulty::Hard, 200);
+
+        assert_eq!(hard_losses, 0, "Hard should never lose");
+        assert!(
+            medium_losses > hard_losses,
+            "Medium should lose more than Hard"
+        );
+        assert!(
+            easy_losses > medium_losses,
+            "Easy should lose more than Medium"
+        );
+        assert!(easy_wins < hard_wins, "Easy should win less than Hard");
+    }
+
+    #[test]
+    fn test_seat_assignment_turn_ownership() {
+        let seats = SeatAssignment::default();
+        assert!(!seats.is_ai_turn(GameMode::HumanVsHuman, Player::X));
+        assert!(!seats.is_ai_turn(GameMode::HumanVsHuman, Player::O));
+        assert!(!seats.is_ai_turn(GameMode::HumanVsAI, Player::X));
+        assert!(seats.is_ai_turn(GameMode::HumanVsAI, Player::O));
+        assert!(seats.is_ai_turn(GameMode::AIVsAI, Player::X));
+        assert!(seats.is_ai_turn(GameMode::AIVsAI, Player::O));
+
+        let human_plays_o = SeatAssignment {
+            human_player: Player::O,
+            ..default()
+        };
+        assert!(human_plays_o.is_ai_turn(GameMode::HumanVsAI, Player::X));
+        assert!(!human_plays_o.is_ai_turn(GameMode::HumanVsAI, Player::O));
+    }
+
+    #[test]
+    fn test_seat_assignment_alternates_first_player() {
+        let fixed = SeatAssignment {
+            first_player: Player::O,
+            ..default()
+        };
+        assert_eq!(
+            BoardState::new(GameRules::default(), Player::O).current_player,
+            Player::O
+        );
+        let rules = GameRules::default();
+        assert_eq!(fixed.next_starting_player(rules, Player::O), Player::O);
+
+        let alternating = SeatAssignment {
+            alternate_first: true,
+            ..default()
+        };
+        assert_eq!(
+            alternating.next_starting_player(rules, Player::X),
+            Player::O
+        );
+        assert_eq!(
+            alternating.next_starting_player(rules, Player::O),
+            Player::X
+        );
+    }
+
+    #[test]
+    fn test_get_best_move_for_x() {
+        let board = create_test_board(&[(1, 1, Player::O), (2, 0, Player::O)]);
+        let ai_move = get_best_move(&board, Player::X);
+        assert_eq!(ai_move, Some((0, 2)), "AI playing X should block O");
+    }
+
+    #[test]
+    fn test_undo_redo_single_move() {
+        let mut state = BoardState::new(GameRules::default(), Player::X);
+        state.play((1, 1), MoveKind::Place);
+        state.play((0, 0), MoveKind::Place);
+
+        let undone = state.undo().expect("a move to undo");
+        assert_eq!(undone.player, Player::O);
+        assert_eq!(state.board[0][0], None);
+        assert_eq!(state.current_player, Player::O);
+        assert_eq!(state.moves.len(), 1);
+
+        let redone = state.redo().expect("a move to redo");
+        assert_eq!(redone, undone);
+        assert_eq!(state.board[0][0], Some(Player::O));
+        assert_eq!(state.current_player, Player::X);
+
+        // A fresh move clears the redo stack
+        state.undo();
+        state.play((2, 2), MoveKind::Place);
+        assert!(state.redo().is_none());
+    }
+
+    #[test]
+    fn test_undo_turn_takes_back_ai_reply_and_human_move() {
+        let seats = SeatAssignment::default();
+        let is_ai_turn = |player| seats.is_ai_turn(GameMode::HumanVsAI, player);
+
+        let mut state = BoardState::new(GameRules::default(), Player::X);
+        state.play((1, 1), MoveKind::Place); // human X
+        state.play((0, 0), MoveKind::Place); // AI O
+        state.play((0, 2), MoveKind::Place); // human X
+        state.play((2, 0), MoveKind::Place); // AI O
+
+        let undone = state.undo_turn(is_ai_turn);
+        assert_eq!(undone.len(), 2);
+        assert_eq!(state.current_player, Player::X);
+        assert_eq!(state.board[0][2], None);
+        assert_eq!(state.board[2][0], None);
+        assert_eq!(state.moves.len(), 2);
+
+        let redone = state.redo_turn(is_ai_turn);
+        assert_eq!(redone.len(), 2);
+        assert_eq!(state.board[0][2], Some(Player::X));
+        assert_eq!(state.board[2][0], Some(Player::O));
+        assert_eq!(state.current_player, Player::X);
+    }
+
+    #[test]
+    fn test_undo_turn_without_human_move_does_nothing() {
+        let seats = SeatAssignment {
+            human_player: Player::O,
+            ..default()
+        };
+        let mut state = BoardState::new(GameRules::default(), Player::X);
+        state.play((1, 1), MoveKind::Place); // AI X opens
+
+        let undone = state.undo_turn(|player| seats.is_ai_turn(GameMode::HumanVsAI, player));
+        assert!(undone.is_empty());
+        assert_eq!(state.board[1][1], Some(Player::X));
+    }
+
+    #[test]
+    fn test_save_data_round_trip() {
+        let data = SaveData {
+            stats: GameStats {
+                x_wins: 3,
+                o_wins: 1,
+                triangle_wins: 0,
+                x_split_wins: 0,
+                o_split_wins: 1,
+                draws: 5,
+                total_games: 10,
+            },
+            game_mode: GameMode::HumanVsHuman,
+            difficulty: Difficulty::Easy,
+            theme: Theme::Dark,
+            seats: SeatAssignment {
+                human_player: Player::O,
+                first_player: Player::O,
+                alternate_first: true,
+            },
+            rules: GameRules {
+                variant: GameVariant::Ultimate,
+                board: BoardConfig::new(15, 5),
+                misere: true,
+                notakto_boards: 2,
+                turn_order: TurnOrder::XTriangleO,
+                swap: true,
+            },
+            setup: GameSetup {
+                random_blocked: 4,
+                blocked: Vec::new(),
+                handicap: 1,
+            },
+            games: vec![sample_game_record()],
+        };
+
+        let json = data.to_json().expect("save data serializes");
+        assert_eq!(SaveData::from_json(&json).expect("save data parses"), data);
+    }
+
+    #[test]
+    fn test_save_data_missing_fields_use_defaults() {
+        let data = SaveData::from_json(r#"{ "theme": "Dark" }"#).expect("partial save parses");
+        assert_eq!(data.theme, Theme::Dark);
+        assert_eq!(data.difficulty, SaveData::default().difficulty);
+        assert_eq!(data.stats, GameStats::default());
+
+        assert!(SaveData::from_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_older_saves_keep_their_board() {
+        let data = SaveData::from_json(
+            r#"{
+                "board": { "size": 7, "win_length": 4 },
+                "games": [{
+                    "timestamp": 1700000000,
+                    "game_mode": "HumanVsHuman",
+                    "difficulty": "Easy",
+                    "human_player": "X",
+                    "first_player": "X",
+                    "board": { "size": 5, "win_length": 4 },
+                    "moves": [],
+                    "result": "Draw"
+                }]
+            }"#,
+        )
+        .expect("older save parses");
+        assert_eq!(data.rules.variant, GameVariant::Standard);
+        assert_eq!(data.rules.board, BoardConfig::new(7, 4));
+        assert_eq!(data.games[0].rules.board, BoardConfig::new(5, 4));
+    }
+
+    #[test]
+    fn test_move_records_save_one_field_per_kind() {
+        let record: MoveRecord = serde_json::from_str(
+            r#"{ "row": 0, "col": 1, "player": "O", "from": [2, 2], "size": "Large" }"#,
+        )
+        .expect("move record parses");
+        assert_eq!(
+            record.kind,
+            MoveKind::Gobblet {
+                size: PieceSize::Large,
+                from: Some((2, 2))
+            }
+        );
+
+        let spooky = MoveRecord {
+            row: 1,
+            col: 1,
+            player: Player::X,
+            kind: MoveKind::Quantum { partner: (0, 2) },
+        };
+        let json = serde_json::to_string(&spooky).expect("move record serializes");
+        assert!(json.contains(r#""partner":[0,2]"#));
+        assert_eq!(serde_json::from_str::<MoveRecord>(&json).ok(), Some(spooky));
+    }
+
+    fn sample_game_record() -> GameRecord {
+        GameRecord {
+            timestamp: 1_700_000_000,
+            game_mode: GameMode::HumanVsAI,
+            difficulty: Difficulty::Hard,
+            human_player: Player::X,
+            first_player: Player::X,
+            rules: GameRules::default(),
+            start: StartingPosition::default(),
+            moves: vec![
+                MoveRecord {
+                    row: 1,
+                    col: 1,
+                    player: Player::X,
+                    kind: MoveKind::Place,
+                },
+                MoveRecord {
+                    row: 0,
+                    col: 0,
+                    player: Player::O,
+                    kind: MoveKind::Place,
+                },
+            ],
+            result: GameResult::Draw,
+        }
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14T22:13:20Z");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+    }
+
+    #[test]
+    fn test_export_csv_and_json() {
+        let stats = GameStats {
+            x_wins: 0,
+            o_wins: 0,
+            triangle_wins: 0,
+            x_split_wins: 0,
+            o_split_wins: 0,
+            draws: 1,
+            total_games: 1,
+        };
+        let games = [sample_game_record()];
+        let data = ExportData {
+            exported_at: format_timestamp(1_700_000_100),
+            stats: &stats,
+            games: &games,
+        };
+
+        let csv = to_csv(&data);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "timestamp,mode,difficulty,human_player,first_player,rules,moves,result"
+        );
+        assert_eq!(
+            lines[1],
+            "2023-11-14T22:13:20Z,Human vs AI,Hard,X,X,Standard 3×3,\"X(1,1) O(0,0)\",Draw"
+        );
+        assert_eq!(lines[2], "");
+        assert_eq!(
+            lines[3],
+            "x_wins,o_wins,triangle_wins,x_split_wins,o_split_wins,draws,total_games"
+        );
+        assert_eq!(lines[4], "0,0,0,0,0,1,1");
+
+        let json: serde_json::Value =
+            serde_json::from_str(&to_json(&data).expect("export serializes")).expect("valid JSON");
+        assert_eq!(json["stats"]["draws"], 1);
+        assert_eq!(json["games"][0]["moves"][1]["player"], "O");
+        assert_eq!(json["games"][0]["result"], "Draw");
+    }
 }
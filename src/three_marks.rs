@@ -0,0 +1,293 @@
+use crate::ai_logic::evaluate_board;
+use crate::config::THREE_MARKS_HARD_AI_DEPTH;
+use crate::search::{Game, Outcome, SearchBoard};
+use crate::types::{find_winning_line, Board, GameVariant, MoveRecord, Player};
+
+// Three-marks variants on the classic board: each player has at most three marks
+// down at once. Under `Disappearing` rules placing a fourth mark removes that
+// player's oldest one; under `Sliding` rules (as in Three Men's Morris) a player
+// with all three down must move one of them to an empty cell instead. Positions
+// can repeat, so a game is drawn once the same position comes up a third time.
+
+pub const MAX_MARKS: usize = 3;
+pub const REPETITION_DRAW_COUNT: usize = 3;
+
+// A move in these variants: a mark lands on `to`, and the mark on `from`
+// (if any) leaves the board at the same time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarkMove {
+    pub from: Option<(usize, usize)>,
+    pub to: (usize, usize),
+}
+
+// Each player's marks on the board, oldest first; indexed by `seat`.
+type Marks = [Vec<(usize, usize)>; 2];
+
+fn seat(player: Player) -> usize {
+    match player {
+        Player::X => 0,
+        Player::O => 1,
+        Player::Triangle => unreachable!("three-marks games have two players"),
+    }
+}
+
+fn apply_to_marks(
+    marks: &mut Marks,
+    player: Player,
+    from: Option<(usize, usize)>,
+    to: (usize, usize),
+) {
+    let own = &mut marks[seat(player)];
+    if let Some(from) = from {
+        own.retain(|&cell| cell != from);
+    }
+    own.push(to);
+}
+
+// Replays the move list to find which marks are down and in what order.
+fn marks_after(moves: &[MoveRecord]) -> Marks {
+    let mut marks = Marks::default();
+    for record in moves {
+        apply_to_marks(
+            &mut marks,
+            record.player,
+            record.vacated(),
+            (record.row, record.col),
+        );
+    }
+    marks
+}
+
+fn mark_count(board: &Board, player: Player) -> usize {
+    board
+        .rows()
+        .flatten()
+        .filter(|&&cell| cell == Some(player))
+        .count()
+}
+
+// Whether `player` has all their marks down and must move one instead of
+// placing a new mark.
+pub fn must_move_mark(variant: GameVariant, board: &Board, player: Player) -> bool {
+    variant == GameVariant::Sliding && mark_count(board, player) >= MAX_MARKS
+}
+
+// The mark that disappears when `player` places their next one: their oldest,
+// once all of them are down under `Disappearing` rules.
+pub fn disappearing_mark(
+    variant: GameVariant,
+    moves: &[MoveRecord],
+    player: Player,
+) -> Option<(usize, usize)> {
+    if variant != GameVariant::Disappearing {
+        return None;
+    }
+    let marks = marks_after(moves);
+    let own = &marks[seat(player)];
+    (own.len() >= MAX_MARKS).then(|| own[0])
+}
+
+// Whether `player` may put a mark on `to`, moving it from `from` when sliding.
+pub fn is_legal_move(
+    variant: GameVariant,
+    board: &Board,
+    player: Player,
+    from: Option<(usize, usize)>,
+    to: (usize, usize),
+) -> bool {
+    if board[to.0][to.1].is_some() {
+        return false;
+    }
+    match from {
+        None => !must_move_mark(variant, board, player),
+        Some((row, col)) => {
+            must_move_mark(variant, board, player) && board[row][col] == Some(player)
+        }
+    }
+}
+
+// Identifies a position for repetition checks: the marks of both players and
+// who is to move. The order of a player's marks only matters when the oldest
+// one will disappear.
+fn position_key(variant: GameVariant, marks: &Marks, to_move: Player) -> u64 {
+    let mut key = seat(to_move) as u64;
+    for own in marks {
+        let mut cells: Vec<u64> = own
+            .iter()
+            .map(|&(row, col)| (row * 3 + col) as u64)
+            .collect();
+        if variant != GameVariant::Disappearing {
+            cells.sort_unstable();
+        }
+        for cell in cells {
+            key = (key << 4) | (cell + 1);
+        }
+        // Separates the two players' marks
+        key <<= 4;
+    }
+    key
+}
+
+// Keys of every position reached so far, one per move played.
+fn position_history(variant: GameVariant, moves: &[MoveRecord]) -> Vec<u64> {
+    let mut marks = Marks::default();
+    moves
+        .iter()
+        .map(|record| {
+            apply_to_marks(
+                &mut marks,
+                record.player,
+                record.vacated(),
+                (record.row, record.col),
+            );
+            position_key(variant, &marks, record.player.opposite())
+        })
+        .collect()
+}
+
+// Whether the game has ended in a draw: no line, and the current position
+// has now occurred `REPETITION_DRAW_COUNT` times.
+pub fn is_draw(variant: GameVariant, board: &Board, moves: &[MoveRecord]) -> bool {
+    find_winning_line(board).is_none() && is_repetition_draw(variant, moves)
+}
+
+// Whether the current position has now occurred `REPETITION_DRAW_COUNT` times.
+fn is_repetition_draw(variant: GameVariant, moves: &[MoveRecord]) -> bool {
+    let history = position_history(variant, moves);
+    let Some(current) = history.last() else {
+        return false;
+    };
+    history.iter().filter(|&key| key == current).count() >= REPETITION_DRAW_COUNT
+}
+
+// Moves for `player`: a slide of one of their marks once all are down under
+// `Sliding` rules, otherwise a new mark (the disappearing one goes by itself).
+fn legal_moves(
+    variant: GameVariant,
+    board: &Board,
+    marks: &Marks,
+    player: Player,
+) -> Vec<MarkMove> {
+    let empty: Vec<_> = board.empty_cells().collect();
+    if !must_move_mark(variant, board, player) {
+        return empty
+            .into_iter()
+            .map(|to| MarkMove { from: None, to })
+            .collect();
+    }
+    marks[seat(player)]
+        .iter()
+        .flat_map(|&from| {
+            empty.iter().map(move |&to| MarkMove {
+                from: Some(from),
+                to,
+            })
+        })
+        .collect()
+}
+
+// A three-marks game as the shared search sees it, with O maximizing. Moves
+// are played on the board and the mark lists in place. `seen` holds the
+// positions of the game so far and of the current search path; reaching one
+// of them again is a draw, since either side could keep repeating.
+pub struct ThreeMarksGame {
+    variant: GameVariant,
+    search_board: SearchBoard,
+    marks: Marks,
+    // For each move played, the mark that left the board and where it stood
+    // in its owner's list.
+    removed: Vec<Option<(usize, (usize, usize))>>,
+    seen: Vec<u64>,
+    to_move: Player,
+    misere: bool,
+}
+
+impl ThreeMarksGame {
+    // The position reached by `moves`, with `to_move` to play.
+    pub fn new(
+        variant: GameVariant,
+        board: &Board,
+        moves: &[MoveRecord],
+        to_move: Player,
+        misere: bool,
+    ) -> Self {
+        ThreeMarksGame {
+            variant,
+            search_board: SearchBoard::new(board),
+            marks: marks_after(moves),
+            removed: Vec::new(),
+            seen: position_history(variant, moves),
+            to_move,
+            misere,
+        }
+    }
+}
+
+impl Game for ThreeMarksGame {
+    type Move = MarkMove;
+
+    const NAME: &'static str = "Three-marks";
+    const HARD_DEPTH: i32 = THREE_MARKS_HARD_AI_DEPTH;
+
+    fn maximizing(&self) -> bool {
+        self.to_move == Player::O
+    }
+
+    fn legal_moves(&self) -> Vec<MarkMove> {
+        legal_moves(
+            self.variant,
+            &self.search_board.board,
+            &self.marks,
+            self.to_move,
+        )
+    }
+
+    fn apply(&mut self, mark_move: MarkMove) {
+        self.seen
+            .push(position_key(self.variant, &self.marks, self.to_move));
+        let own = &mut self.marks[seat(self.to_move)];
+        let from = mark_move.from.or_else(|| {
+            (self.variant == GameVariant::Disappearing && own.len() >= MAX_MARKS).then(|| own[0])
+        });
+        let removed = from.and_then(|from| {
+            let index = own.iter().position(|&cell| cell == from)?;
+            own.remove(index);
+            Some((index, from))
+        });
+        if let Some((_, (row, col))) = removed {
+            self.search_board.board[row][col] = None;
+        }
+        own.push(mark_move.to);
+        self.removed.push(removed);
+        self.search_board.place(mark_move.to, self.to_move);
+        self.to_move = self.to_move.opposite();
+    }
+
+    fn undo(&mut self, mark_move: MarkMove) {
+        self.to_move = self.to_move.opposite();
+        self.search_board.take_back(mark_move.to);
+        let own = &mut self.marks[seat(self.to_move)];
+        own.pop();
+        if let Some((index, (row, col))) = self.removed.pop().flatten() {
+            own.insert(index, (row, col));
+            self.search_board.board[row][col] = Some(self.to_move);
+        }
+        self.seen.pop();
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        if let Some(line) = self.search_board.completed_line() {
+            return Some(Outcome::Win(line.player == Player::O));
+        }
+        let key = position_key(self.variant, &self.marks, self.to_move);
+        self.seen.contains(&key).then_some(Outcome::Draw)
+    }
+
+    fn evaluate(&self) -> i32 {
+        evaluate_board(&self.search_board.board)
+    }
+
+    fn misere(&self) -> bool {
+        self.misere
+    }
+}
@@ -0,0 +1,109 @@
+use crate::ai_logic::{candidate_moves, depth_cap, line_score};
+use crate::config::THREE_PLAYER_HARD_AI_DEPTH;
+use crate::search::{Game, Outcome, SearchBoard};
+use crate::types::{Board, Player, TurnOrder};
+
+// Three-player tic-tac-toe: X, O and Δ take turns in the chosen order on a
+// board preset of at least 4×4, and the first to complete a line wins.
+
+pub const MIN_BOARD_SIZE: usize = 4;
+
+// Heuristic score of a non-terminal board from `player`'s point of view.
+// Lines still open to only one player count towards that player, and both
+// opponents' lines count against `player` alike.
+fn evaluate(board: &Board, player: Player) -> i32 {
+    board
+        .lines()
+        .iter()
+        .map(|line| {
+            let mut owner = None;
+            let mut count = 0;
+            for &(row, col) in line.cells.iter() {
+                match (board[row][col], owner) {
+                    (None, _) => {}
+                    (Some(mark), None) => {
+                        owner = Some(mark);
+                        count = 1;
+                    }
+                    (Some(mark), Some(previous)) if mark == previous => count += 1,
+                    // Blocked by two players
+                    (Some(_), Some(_)) => return 0,
+                }
+            }
+            match owner {
+                Some(owner) if owner == player => line_score(count),
+                Some(_) => -line_score(count),
+                None => 0,
+            }
+        })
+        .sum()
+}
+
+// A three-player game as the shared search sees it, searched paranoid:
+// `player` maximizes, and the other two players are assumed to team up
+// against it and minimize, which turns the three-way game back into a
+// two-sided one.
+pub struct ThreePlayerGame {
+    search_board: SearchBoard,
+    to_move: Player,
+    player: Player,
+    turn_order: TurnOrder,
+}
+
+impl ThreePlayerGame {
+    pub fn new(board: &Board, player: Player, turn_order: TurnOrder) -> Self {
+        ThreePlayerGame {
+            search_board: SearchBoard::new(board),
+            to_move: player,
+            player,
+            turn_order,
+        }
+    }
+}
+
+impl Game for ThreePlayerGame {
+    type Move = (usize, usize);
+
+    const NAME: &'static str = "Three-player";
+    const HARD_DEPTH: i32 = THREE_PLAYER_HARD_AI_DEPTH;
+
+    fn maximizing(&self) -> bool {
+        self.to_move == self.player
+    }
+
+    fn legal_moves(&self) -> Vec<(usize, usize)> {
+        self.search_board.board.empty_cells().collect()
+    }
+
+    fn search_moves(&self) -> Vec<(usize, usize)> {
+        candidate_moves(&self.search_board.board)
+    }
+
+    fn apply(&mut self, cell: (usize, usize)) {
+        self.search_board.place(cell, self.to_move);
+        self.to_move = self.turn_order.after(self.to_move);
+    }
+
+    fn undo(&mut self, (row, col): (usize, usize)) {
+        // The mark taken back tells whose turn it was
+        if let Some(mover) = self.search_board.board[row][col] {
+            self.to_move = mover;
+        }
+        self.search_board.take_back((row, col));
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        if let Some(line) = self.search_board.completed_line() {
+            return Some(Outcome::Win(line.player == self.player));
+        }
+        self.search_board.is_full().then_some(Outcome::Draw)
+    }
+
+    fn evaluate(&self) -> i32 {
+        evaluate(&self.search_board.board, self.player)
+    }
+
+    fn depth_cap(&self) -> i32 {
+        depth_cap(&self.search_board.board)
+    }
+}
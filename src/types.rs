@@ -1,18 +1,31 @@
-use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::ops::{Index, IndexMut};
+use std::sync::Arc;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::gobblet::PieceSize;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum Player {
     #[default]
     X,
     O,
+    // The third player, only in three-player games.
+    Triangle,
 }
 
 impl Player {
-    // Returns the character representation of the player('X' or 'O').
+    // The two symbols of every game but three-player ones.
+    pub const ALL: [Player; 2] = [Player::X, Player::O];
+    pub const THREE_PLAYERS: [Player; 3] = [Player::X, Player::O, Player::Triangle];
+
+    // Returns the character representation of the player('X', 'O' or 'Δ').
     pub fn to_char(self) -> char {
         match self {
             Player::X => 'X',
             Player::O => 'O',
+            Player::Triangle => 'Δ',
         }
     }
 
@@ -23,10 +36,72 @@ impl Player {
     }
 
     // Returns the opposite player. Useful for AI minimax logic.
+    // Three-player games pass the turn by `TurnOrder` instead.
     pub fn opposite(self) -> Player {
         match self {
             Player::X => Player::O,
             Player::O => Player::X,
+            Player::Triangle => unreachable!("Δ only plays in three-player games"),
+        }
+    }
+}
+
+// The order three players take turns in, whoever opens the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TurnOrder {
+    #[default]
+    XOTriangle,
+    XTriangleO,
+}
+
+impl TurnOrder {
+    pub const ALL: [TurnOrder; 2] = [TurnOrder::XOTriangle, TurnOrder::XTriangleO];
+
+    // Returns the name shown in menus.
+    pub fn label(self) -> &'static str {
+        match self {
+            TurnOrder::XOTriangle => "X → O → Δ",
+            TurnOrder::XTriangleO => "X → Δ → O",
+        }
+    }
+
+    // The player who moves after `player`.
+    pub fn after(self, player: Player) -> Player {
+        match (self, player) {
+            (TurnOrder::XOTriangle, Player::X) | (TurnOrder::XTriangleO, Player::Triangle) => {
+                Player::O
+            }
+            (TurnOrder::XOTriangle, Player::O) | (TurnOrder::XTriangleO, Player::X) => {
+                Player::Triangle
+            }
+            (TurnOrder::XOTriangle, Player::Triangle) | (TurnOrder::XTriangleO, Player::O) => {
+                Player::X
+            }
+        }
+    }
+}
+
+// The sides in Order and Chaos, whichever symbols they place: Order wants
+// five in a row, Chaos a full board without one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Role {
+    Order,
+    Chaos,
+}
+
+impl Role {
+    // Returns the name shown in the HUD and exports.
+    pub fn label(self) -> &'static str {
+        match self {
+            Role::Order => "Order",
+            Role::Chaos => "Chaos",
+        }
+    }
+
+    pub fn other(self) -> Role {
+        match self {
+            Role::Order => Role::Chaos,
+            Role::Chaos => Role::Order,
         }
     }
 }
@@ -39,74 +114,1094 @@ pub enum CellState {
     Occupied(Player),
 }
 
-pub type Board = [[Option<Player>; 3]; 3];
+// How the edges of the board behave. On a torus a line running off one edge
+// comes back in at the opposite one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Topology {
+    #[default]
+    Flat,
+    Torus,
+}
 
-pub fn check_winner(board: &Board) -> Option<Player> {
-    // Check rows
-    for row in board {
-        if let Some(player) = row[0] {
-            if row[1] == Some(player) && row[2] == Some(player) {
-                return Some(player);
+// Size of the square board, how many marks in a row win on it and whether
+// lines wrap around its edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoardConfig {
+    pub size: usize,
+    pub win_length: usize,
+    // Older saves predate wrapped boards.
+    #[serde(default)]
+    pub topology: Topology,
+}
+
+impl Default for BoardConfig {
+    fn default() -> Self {
+        BoardConfig::CLASSIC
+    }
+}
+
+impl BoardConfig {
+    pub const CLASSIC: BoardConfig = BoardConfig::new(3, 3);
+
+    // Boards offered in the menu, from classic tic-tac-toe up to Gomoku.
+    // 7×7 with four in a row plays like Connect Four under gravity rules.
+    pub const PRESETS: [BoardConfig; 5] = [
+        BoardConfig::CLASSIC,
+        BoardConfig::new(4, 4),
+        BoardConfig::new(5, 4),
+        BoardConfig::new(7, 4),
+        BoardConfig::new(15, 5),
+    ];
+
+    pub const fn new(size: usize, win_length: usize) -> Self {
+        BoardConfig {
+            size,
+            win_length,
+            topology: Topology::Flat,
+        }
+    }
+
+    // The same board with the given edges.
+    pub const fn with_topology(self, topology: Topology) -> Self {
+        BoardConfig { topology, ..self }
+    }
+
+    // Returns the name shown in menus, e.g. "15×15, 5 in a row".
+    pub fn label(self) -> String {
+        let label = if self.size == self.win_length {
+            format!("{}×{}", self.size, self.size)
+        } else {
+            format!("{}×{}, {} in a row", self.size, self.size, self.win_length)
+        };
+        match self.topology {
+            Topology::Flat => label,
+            Topology::Torus => format!("{}, wrapped", label),
+        }
+    }
+
+    // The preset after this one, wrapping around; used by the menu's cycle
+    // button. The edges stay as they are.
+    pub fn next_preset(self) -> BoardConfig {
+        let index = BoardConfig::PRESETS
+            .iter()
+            .position(|preset| preset.size == self.size && preset.win_length == self.win_length)
+            .map_or(0, |index| index + 1);
+        BoardConfig::PRESETS[index % BoardConfig::PRESETS.len()].with_topology(self.topology)
+    }
+}
+
+// Which game is played on the board.
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Default, Serialize, Deserialize)]
+pub enum GameVariant {
+    // Classic m,n,k rules on the chosen board preset.
+    #[default]
+    Standard,
+    // A 3×3 grid of 3×3 boards; see `ultimate.rs`.
+    Ultimate,
+    // Four in a row on a 4×4×4 cube; see `qubic.rs`.
+    Qubic,
+    // Marks drop to the bottom of their column, as in Connect Four; see `gravity.rs`.
+    Gravity,
+    // Three marks each on 3×3; the oldest disappears when a fourth is placed.
+    // See `three_marks.rs`.
+    Disappearing,
+    // Three marks each on 3×3, then marks are moved (Three Men's Morris).
+    Sliding,
+    // Either player may place either symbol; see `wild.rs`.
+    Wild,
+    // Both players place X on several 3×3 boards; whoever completes the
+    // last board's line loses. See `notakto.rs`.
+    Notakto,
+    // Each move puts a mark in two cells until entanglement collapses it;
+    // see `quantum.rs`.
+    Quantum,
+    // Five in a row of either symbol on 6×6 wins for Order, a full board for
+    // Chaos; see `order_chaos.rs`.
+    OrderAndChaos,
+    // Pieces of three sizes that may cover smaller ones; see `gobblet.rs`.
+    Gobblet,
+    // X, O and Δ take turns on a 4×4 or larger board preset; see
+    // `three_player.rs`.
+    ThreePlayer,
+}
+
+impl GameVariant {
+    pub const ALL: [GameVariant; 12] = [
+        GameVariant::Standard,
+        GameVariant::Wild,
+        GameVariant::Gravity,
+        GameVariant::Ultimate,
+        GameVariant::Qubic,
+        GameVariant::Disappearing,
+        GameVariant::Sliding,
+        GameVariant::Notakto,
+        GameVariant::Quantum,
+        GameVariant::OrderAndChaos,
+        GameVariant::Gobblet,
+        GameVariant::ThreePlayer,
+    ];
+
+    // Returns the name shown in menus.
+    pub fn label(self) -> &'static str {
+        match self {
+            GameVariant::Standard => "Standard",
+            GameVariant::Ultimate => "Ultimate",
+            GameVariant::Qubic => "Qubic",
+            GameVariant::Gravity => "Gravity",
+            GameVariant::Disappearing => "Disappearing",
+            GameVariant::Sliding => "Sliding",
+            GameVariant::Wild => "Wild",
+            GameVariant::Notakto => "Notakto",
+            GameVariant::Quantum => "Quantum",
+            GameVariant::OrderAndChaos => "Order and Chaos",
+            GameVariant::Gobblet => "Gobblet",
+            GameVariant::ThreePlayer => "Three Players",
+        }
+    }
+
+    // The variant after this one, wrapping around; used by the menu's cycle button.
+    pub fn next(self) -> GameVariant {
+        let index = GameVariant::ALL
+            .iter()
+            .position(|&v| v == self)
+            .unwrap_or(0);
+        GameVariant::ALL[(index + 1) % GameVariant::ALL.len()]
+    }
+
+    // Whether the board size comes from the selected preset.
+    pub fn uses_board_preset(self) -> bool {
+        match self {
+            GameVariant::Standard
+            | GameVariant::Gravity
+            | GameVariant::Wild
+            | GameVariant::ThreePlayer => true,
+            GameVariant::Ultimate
+            | GameVariant::Qubic
+            | GameVariant::Disappearing
+            | GameVariant::Sliding
+            | GameVariant::Notakto
+            | GameVariant::Quantum
+            | GameVariant::OrderAndChaos
+            | GameVariant::Gobblet => false,
+        }
+    }
+
+    // Whether the swap rule applies: two-player games on the board presets
+    // where each move places the mover's own mark.
+    pub fn allows_swap(self) -> bool {
+        match self {
+            GameVariant::Standard | GameVariant::Gravity => true,
+            GameVariant::Ultimate
+            | GameVariant::Qubic
+            | GameVariant::Disappearing
+            | GameVariant::Sliding
+            | GameVariant::Wild
+            | GameVariant::Notakto
+            | GameVariant::Quantum
+            | GameVariant::OrderAndChaos
+            | GameVariant::Gobblet
+            | GameVariant::ThreePlayer => false,
+        }
+    }
+
+    // Whether the misère option changes who a line wins for. Notakto is always
+    // played that way round, a line in Order and Chaos is always Order's, and
+    // a losing line in a three-player game would leave two winners.
+    pub fn allows_misere(self) -> bool {
+        match self {
+            GameVariant::Standard
+            | GameVariant::Ultimate
+            | GameVariant::Qubic
+            | GameVariant::Gravity
+            | GameVariant::Disappearing
+            | GameVariant::Sliding
+            | GameVariant::Wild
+            | GameVariant::Quantum
+            | GameVariant::Gobblet => true,
+            GameVariant::Notakto | GameVariant::OrderAndChaos | GameVariant::ThreePlayer => false,
+        }
+    }
+
+    // Whether players choose which symbol to place, with the symbol picker.
+    pub fn places_either_symbol(self) -> bool {
+        match self {
+            GameVariant::Wild | GameVariant::OrderAndChaos => true,
+            GameVariant::Standard
+            | GameVariant::Ultimate
+            | GameVariant::Qubic
+            | GameVariant::Gravity
+            | GameVariant::Disappearing
+            | GameVariant::Sliding
+            | GameVariant::Notakto
+            | GameVariant::Quantum
+            | GameVariant::Gobblet
+            | GameVariant::ThreePlayer => false,
+        }
+    }
+
+    // Whether marks are redrawn from the whole position after every change,
+    // rather than spawned and despawned one move at a time.
+    pub fn redraws_marks(self) -> bool {
+        match self {
+            GameVariant::Quantum | GameVariant::Gobblet => true,
+            GameVariant::Standard
+            | GameVariant::Ultimate
+            | GameVariant::Qubic
+            | GameVariant::Gravity
+            | GameVariant::Disappearing
+            | GameVariant::Sliding
+            | GameVariant::Wild
+            | GameVariant::Notakto
+            | GameVariant::OrderAndChaos
+            | GameVariant::ThreePlayer => false,
+        }
+    }
+
+    // Smallest board preset the variant is played on; three players need
+    // room for all their lines.
+    pub fn min_board_size(self) -> usize {
+        match self {
+            GameVariant::ThreePlayer => crate::three_player::MIN_BOARD_SIZE,
+            GameVariant::Standard
+            | GameVariant::Ultimate
+            | GameVariant::Qubic
+            | GameVariant::Gravity
+            | GameVariant::Disappearing
+            | GameVariant::Sliding
+            | GameVariant::Wild
+            | GameVariant::Notakto
+            | GameVariant::Quantum
+            | GameVariant::OrderAndChaos
+            | GameVariant::Gobblet => BoardConfig::CLASSIC.size,
+        }
+    }
+}
+
+// Everything that decides how a game is played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameRules {
+    pub variant: GameVariant,
+    // Board preset for variants that use one.
+    pub board: BoardConfig,
+    // Misère play: completing a line loses instead of winning.
+    pub misere: bool,
+    // Number of boards in Notakto.
+    pub notakto_boards: usize,
+    // Who follows whom in three-player games.
+    pub turn_order: TurnOrder,
+    // Swap (pie) rule: after the first move, the second player may take it
+    // over instead of replying.
+    pub swap: bool,
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        GameRules {
+            variant: GameVariant::default(),
+            board: BoardConfig::default(),
+            misere: false,
+            notakto_boards: crate::notakto::MAX_BOARDS,
+            turn_order: TurnOrder::default(),
+            swap: false,
+        }
+    }
+}
+
+impl GameRules {
+    // An empty board for these rules; some variants fix its shape.
+    pub fn new_board(self) -> Board {
+        match self.variant {
+            GameVariant::Standard
+            | GameVariant::Gravity
+            | GameVariant::Wild
+            | GameVariant::ThreePlayer => Board::new(self.board),
+            GameVariant::Ultimate => Board::new(crate::ultimate::ULTIMATE_BOARD),
+            GameVariant::Qubic => crate::qubic::new_board(),
+            GameVariant::Disappearing
+            | GameVariant::Sliding
+            | GameVariant::Quantum
+            | GameVariant::Gobblet => Board::new(BoardConfig::CLASSIC),
+            GameVariant::Notakto => crate::notakto::new_board(self.notakto_board_count()),
+            GameVariant::OrderAndChaos => Board::new(crate::order_chaos::ORDER_AND_CHAOS_BOARD),
+        }
+    }
+
+    // Describes the board for menus, the scoreboard and exports.
+    pub fn board_label(self) -> String {
+        match self.variant {
+            GameVariant::Standard
+            | GameVariant::Gravity
+            | GameVariant::Wild
+            | GameVariant::ThreePlayer => self.board.label(),
+            GameVariant::Ultimate => "3×3 of 3×3".to_string(),
+            GameVariant::Qubic => "4×4×4".to_string(),
+            GameVariant::Disappearing | GameVariant::Sliding => "3×3, 3 marks each".to_string(),
+            GameVariant::Quantum => BoardConfig::CLASSIC.label(),
+            GameVariant::Gobblet => "3×3, 3 sizes".to_string(),
+            GameVariant::OrderAndChaos => crate::order_chaos::ORDER_AND_CHAOS_BOARD.label(),
+            GameVariant::Notakto => match self.notakto_board_count() {
+                1 => "1 board".to_string(),
+                count => format!("{} boards", count),
+            },
+        }
+    }
+
+    // The board preset after the current one that is large enough for the
+    // variant, wrapping around; used by the menu's cycle button.
+    pub fn next_board(self) -> BoardConfig {
+        let mut board = self.board.next_preset();
+        while board.size < self.variant.min_board_size() {
+            board = board.next_preset();
+        }
+        board
+    }
+
+    // The players in turn order, starting with X.
+    pub fn players(self) -> Vec<Player> {
+        if self.variant == GameVariant::ThreePlayer {
+            let second = self.turn_order.after(Player::X);
+            vec![Player::X, second, self.turn_order.after(second)]
+        } else {
+            Player::ALL.to_vec()
+        }
+    }
+
+    // The player who moves after `player`.
+    pub fn next_player(self, player: Player) -> Player {
+        if self.variant == GameVariant::ThreePlayer {
+            self.turn_order.after(player)
+        } else {
+            player.opposite()
+        }
+    }
+
+    // Number of Notakto boards, kept within what the menu offers.
+    pub fn notakto_board_count(self) -> usize {
+        self.notakto_boards.clamp(1, crate::notakto::MAX_BOARDS)
+    }
+
+    // The Notakto board count after this one, wrapping around; used by the
+    // menu's cycle button.
+    pub fn next_notakto_boards(self) -> usize {
+        self.notakto_board_count() % crate::notakto::MAX_BOARDS + 1
+    }
+
+    // Whether the second player may take over the opening move.
+    pub fn swap_allowed(self) -> bool {
+        self.swap && self.variant.allows_swap()
+    }
+
+    // Whether completing a line loses.
+    pub fn misere_applies(self) -> bool {
+        self.misere && self.variant.allows_misere()
+    }
+
+    // Describes the whole rule set, e.g. "Standard 4×4, misère". Three-player
+    // games show their turn order instead.
+    pub fn label(self) -> String {
+        let mut label = format!("{} {}", self.variant.label(), self.board_label());
+        if self.swap_allowed() {
+            label.push_str(", swap");
+        }
+        if self.variant == GameVariant::ThreePlayer {
+            format!("{}, {}", label, self.turn_order.label())
+        } else if self.misere_applies() {
+            format!("{}, misère", label)
+        } else {
+            label
+        }
+    }
+
+    // Who wins when `line_player` completes a line. Notakto is always played
+    // misère: only the line that kills the last board ends the game.
+    pub fn line_winner(self, line_player: Player) -> Player {
+        let reversed = self.variant == GameVariant::Notakto || self.misere_applies();
+        if reversed {
+            line_player.opposite()
+        } else {
+            line_player
+        }
+    }
+}
+
+// How each game on a plain board (see `GameVariant::uses_board_preset`)
+// starts: cells nobody may play in, and handicap marks placed for the weaker
+// side before the first move.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameSetup {
+    // Cells blocked at random in every game.
+    pub random_blocked: usize,
+    // Cells blocked by hand before the first move, kept for the rest of the series.
+    #[serde(skip)]
+    pub blocked: Vec<(usize, usize)>,
+    // Marks placed for the weaker side.
+    pub handicap: usize,
+}
+
+impl GameSetup {
+    pub const RANDOM_BLOCKED_COUNTS: [usize; 4] = [0, 2, 4, 6];
+    pub const HANDICAP_COUNTS: [usize; 3] = [0, 1, 2];
+
+    // The next count the menu's cycle buttons offer, wrapping around.
+    pub fn next_random_blocked(&self) -> usize {
+        next_count(&Self::RANDOM_BLOCKED_COUNTS, self.random_blocked)
+    }
+
+    pub fn next_handicap(&self) -> usize {
+        next_count(&Self::HANDICAP_COUNTS, self.handicap)
+    }
+
+    pub fn random_blocked_label(&self) -> String {
+        match self.random_blocked {
+            0 => "No Blocked Cells".to_string(),
+            count => format!("{} Blocked Cells", count),
+        }
+    }
+
+    pub fn handicap_label(&self) -> String {
+        match self.handicap {
+            0 => "No Handicap".to_string(),
+            1 => "1 Handicap Mark".to_string(),
+            count => format!("{} Handicap Marks", count),
+        }
+    }
+}
+
+fn next_count(counts: &[usize], current: usize) -> usize {
+    counts
+        .iter()
+        .copied()
+        .find(|&count| count > current)
+        .unwrap_or(counts[0])
+}
+
+// What a game actually started from once its setup was applied.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StartingPosition {
+    pub blocked: Vec<(usize, usize)>,
+    pub marks: Vec<((usize, usize), Player)>,
+}
+
+impl StartingPosition {
+    // The board for `rules` with these cells blocked and marks placed.
+    pub fn board(&self, rules: GameRules) -> Board {
+        let mut board = rules.new_board();
+        for &(row, col) in self.blocked.iter() {
+            board.block(row, col);
+        }
+        for &((row, col), mark) in self.marks.iter() {
+            board[row][col] = Some(mark);
+        }
+        board
+    }
+}
+
+// Which row, column or diagonal a line runs along.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    Row(usize),
+    Column(usize),
+    Diagonal,
+    AntiDiagonal,
+    // Qubic: a line inside one layer of the cube, or one through all layers.
+    // Notakto numbers its boards as layers.
+    Layer(usize),
+    CrossLayer,
+}
+
+impl LineKind {
+    // The way a line of this kind runs from one cell to the next, as
+    // (rows, columns); None for Qubic and Notakto lines, whose cells lie in
+    // separately drawn grids.
+    pub fn direction(self) -> Option<(isize, isize)> {
+        match self {
+            LineKind::Row(_) => Some((0, 1)),
+            LineKind::Column(_) => Some((1, 0)),
+            LineKind::Diagonal => Some((1, 1)),
+            LineKind::AntiDiagonal => Some((1, -1)),
+            LineKind::Layer(_) | LineKind::CrossLayer => None,
+        }
+    }
+}
+
+// A run of `win_length` cells that wins the game when one player fills it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub kind: LineKind,
+    pub cells: Vec<(usize, usize)>,
+}
+
+// Every row, column and diagonal segment of `win_length` cells on the board,
+// including those that wrap around the edges of a torus.
+pub fn winning_lines(config: BoardConfig) -> Vec<Line> {
+    match config.topology {
+        Topology::Flat => flat_lines(config.size, config.win_length),
+        Topology::Torus => wrapped_lines(config.size, config.win_length),
+    }
+}
+
+fn flat_lines(size: usize, win_length: usize) -> Vec<Line> {
+    let mut lines = Vec::new();
+    if win_length == 0 || win_length > size {
+        return lines;
+    }
+    let span = size - win_length + 1;
+
+    // Rows
+    for row in 0..size {
+        for start in 0..span {
+            lines.push(Line {
+                kind: LineKind::Row(row),
+                cells: (0..win_length).map(|i| (row, start + i)).collect(),
+            });
+        }
+    }
+    // Columns
+    for col in 0..size {
+        for start in 0..span {
+            lines.push(Line {
+                kind: LineKind::Column(col),
+                cells: (0..win_length).map(|i| (start + i, col)).collect(),
+            });
+        }
+    }
+    // Diagonals
+    for row in 0..span {
+        for col in 0..span {
+            lines.push(Line {
+                kind: LineKind::Diagonal,
+                cells: (0..win_length).map(|i| (row + i, col + i)).collect(),
+            });
+        }
+    }
+    for row in 0..span {
+        for col in (win_length - 1)..size {
+            lines.push(Line {
+                kind: LineKind::AntiDiagonal,
+                cells: (0..win_length).map(|i| (row + i, col - i)).collect(),
+            });
+        }
+    }
+
+    lines
+}
+
+// On a torus a line may start in any cell and run in any of the four
+// directions, leaving the board at one edge and coming back in at the
+// opposite one. Cells are listed from the start, so a line that wraps has a
+// row or column index going backwards where it crosses the edge. A line as
+// long as the board is the same whichever of its cells it starts from and is
+// listed once.
+fn wrapped_lines(size: usize, win_length: usize) -> Vec<Line> {
+    let mut lines = Vec::new();
+    if win_length == 0 || win_length > size {
+        return lines;
+    }
+
+    let mut seen = HashSet::new();
+    let wrap =
+        |index: usize, offset: isize| (index as isize + offset).rem_euclid(size as isize) as usize;
+    for (d_row, d_col) in [(0, 1), (1, 0), (1, 1), (1, -1)] {
+        for row in 0..size {
+            for col in 0..size {
+                let cells: Vec<_> = (0..win_length as isize)
+                    .map(|i| (wrap(row, i * d_row), wrap(col, i * d_col)))
+                    .collect();
+                let mut key = cells.clone();
+                key.sort_unstable();
+                if !seen.insert(key) {
+                    continue;
+                }
+                let kind = match (d_row, d_col) {
+                    (0, _) => LineKind::Row(row),
+                    (_, 0) => LineKind::Column(col),
+                    (_, 1) => LineKind::Diagonal,
+                    _ => LineKind::AntiDiagonal,
+                };
+                lines.push(Line { kind, cells });
+            }
+        }
+    }
+
+    lines
+}
+
+// A grid of marks, indexed as `board[row][col]`, together with the lines that
+// win on it. Square m,n,k boards get their rows, columns and diagonals; other
+// geometries (like Qubic's layers laid side by side) bring their own lines.
+// The lines are computed once per board and shared between clones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    rows: usize,
+    cols: usize,
+    // Whether neighbouring cells continue across the edges.
+    topology: Topology,
+    cells: Vec<Option<Player>>,
+    // Cells nobody may play in, see `Board::block`.
+    blocked: Vec<bool>,
+    lines: Arc<Vec<Line>>,
+    // For each cell, the indices of the lines passing through it.
+    lines_through: Arc<Vec<Vec<usize>>>,
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Board::new(BoardConfig::CLASSIC)
+    }
+}
+
+impl Board {
+    pub fn new(config: BoardConfig) -> Self {
+        Board {
+            topology: config.topology,
+            ..Board::with_lines(config.size, config.size, winning_lines(config))
+        }
+    }
+
+    // An empty `rows` x `cols` board won by completing any of `lines`.
+    pub fn with_lines(rows: usize, cols: usize, lines: Vec<Line>) -> Self {
+        let mut lines_through = vec![Vec::new(); rows * cols];
+        for (index, line) in lines.iter().enumerate() {
+            for &(row, col) in line.cells.iter() {
+                lines_through[row * cols + col].push(index);
+            }
+        }
+
+        Board {
+            rows,
+            cols,
+            topology: Topology::Flat,
+            cells: vec![None; rows * cols],
+            blocked: vec![false; rows * cols],
+            lines: Arc::new(lines),
+            lines_through: Arc::new(lines_through),
+        }
+    }
+
+    // (rows, columns)
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+
+    // Takes a cell out of play: it never holds a mark, and the lines through
+    // it can no longer be completed.
+    pub fn block(&mut self, row: usize, col: usize) {
+        let lines = self
+            .lines
+            .iter()
+            .filter(|line| !line.cells.contains(&(row, col)))
+            .cloned()
+            .collect();
+        let mut blocked = std::mem::take(&mut self.blocked);
+        blocked[row * self.cols + col] = true;
+        *self = Board {
+            topology: self.topology,
+            cells: std::mem::take(&mut self.cells),
+            blocked,
+            ..Board::with_lines(self.rows, self.cols, lines)
+        };
+        self[row][col] = None;
+    }
+
+    pub fn is_blocked(&self, row: usize, col: usize) -> bool {
+        self.blocked[row * self.cols + col]
+    }
+
+    // The winning lines that pass through the given cell.
+    pub fn lines_through(&self, row: usize, col: usize) -> impl Iterator<Item = &Line> {
+        self.lines_through[row * self.cols + col]
+            .iter()
+            .map(|&index| &self.lines[index])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[Option<Player>]> {
+        self.cells.chunks(self.cols)
+    }
+
+    pub fn empty_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let cols = self.cols;
+        self.cells
+            .iter()
+            .zip(self.blocked.iter())
+            .enumerate()
+            .filter(|(_, (cell, &blocked))| cell.is_none() && !blocked)
+            .map(move |(index, _)| (index / cols, index % cols))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.iter().all(|cell| cell.is_none())
+    }
+}
+
+impl Index<usize> for Board {
+    type Output = [Option<Player>];
+
+    fn index(&self, row: usize) -> &Self::Output {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+}
+
+impl IndexMut<usize> for Board {
+    fn index_mut(&mut self, row: usize) -> &mut Self::Output {
+        &mut self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+}
+
+// A completed line: who completed it, where it runs and its cells in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WinningLine {
+    pub player: Player,
+    pub kind: LineKind,
+    pub cells: Vec<(usize, usize)>,
+}
+
+impl WinningLine {
+    // The cells split into runs of neighbours, breaking wherever the line
+    // wraps around the edge of a torus; a line on a flat board is one run.
+    pub fn segments(&self) -> Vec<&[(usize, usize)]> {
+        let Some((d_row, d_col)) = self.kind.direction() else {
+            return vec![&self.cells];
+        };
+        let wraps = |(row, col): (usize, usize), (next_row, next_col): (usize, usize)| {
+            (d_row > 0 && next_row < row)
+                || (d_col > 0 && next_col < col)
+                || (d_col < 0 && next_col > col)
+        };
+
+        let mut segments = Vec::new();
+        let mut start = 0;
+        for index in 1..self.cells.len() {
+            if wraps(self.cells[index - 1], self.cells[index]) {
+                segments.push(&self.cells[start..index]);
+                start = index;
             }
         }
+        segments.push(&self.cells[start..]);
+        segments
+    }
+}
+
+// Returns the line if one player holds every cell of it.
+fn completed_line(board: &Board, line: &Line) -> Option<WinningLine> {
+    let (row, col) = line.cells[0];
+    let player = board[row][col]?;
+    line.cells
+        .iter()
+        .all(|&(r, c)| board[r][c] == Some(player))
+        .then(|| WinningLine {
+            player,
+            kind: line.kind,
+            cells: line.cells.clone(),
+        })
+}
+
+// Returns the first completed line on the board, if any.
+pub fn find_winning_line(board: &Board) -> Option<WinningLine> {
+    board
+        .lines()
+        .iter()
+        .find_map(|line| completed_line(board, line))
+}
+
+// Returns a completed line through the given cell. Cheaper than
+// `find_winning_line` when only the last move can have won.
+pub fn find_winning_line_through(board: &Board, row: usize, col: usize) -> Option<WinningLine> {
+    board
+        .lines_through(row, col)
+        .find_map(|line| completed_line(board, line))
+}
+
+pub fn check_winner(board: &Board) -> Option<Player> {
+    find_winning_line(board).map(|line| line.player)
+}
+
+// What a move does besides putting a mark in its cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveKind {
+    // A new mark of the mover's own.
+    Place,
+    // In the three-marks variants, the mover's mark leaves `from`: moved from
+    // there when sliding, or the oldest one disappearing.
+    Slide {
+        from: (usize, usize),
+    },
+    // A mark of `symbol`, which need not be the mover's own: in wild games,
+    // Order and Chaos and Notakto.
+    Wild {
+        symbol: Player,
+    },
+    // A spooky mark in quantum games, in `partner` as well.
+    Quantum {
+        partner: (usize, usize),
+    },
+    // A Gobblet Gobblers piece of `size`, lifted off the top of `from` or
+    // taken from the reserve.
+    Gobblet {
+        size: PieceSize,
+        from: Option<(usize, usize)>,
+    },
+    // Under the swap rule, takes over the opening move: its mark in the same
+    // cell becomes the mover's.
+    Swap,
+}
+
+// A mark placed on the board, kept in the move history for undo / redo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "SavedMove", into = "SavedMove")]
+pub struct MoveRecord {
+    pub row: usize,
+    pub col: usize,
+    pub player: Player,
+    pub kind: MoveKind,
+}
+
+impl MoveRecord {
+    // The symbol this move put on the board.
+    pub fn mark(&self) -> Player {
+        match self.kind {
+            MoveKind::Wild { symbol } => symbol,
+            MoveKind::Place
+            | MoveKind::Slide { .. }
+            | MoveKind::Quantum { .. }
+            | MoveKind::Gobblet { .. }
+            | MoveKind::Swap => self.player,
+        }
     }
 
-    // Check columns
-    for col in 0..3 {
-        if let Some(player) = board[0][col] {
-            if board[1][col] == Some(player) && board[2][col] == Some(player) {
-                return Some(player);
+    // The cell the mover's mark or piece left with this move.
+    pub fn vacated(&self) -> Option<(usize, usize)> {
+        match self.kind {
+            MoveKind::Slide { from } => Some(from),
+            MoveKind::Gobblet { from, .. } => from,
+            MoveKind::Place | MoveKind::Wild { .. } | MoveKind::Quantum { .. } | MoveKind::Swap => {
+                None
             }
         }
     }
+}
 
-    // Check diagonals
-    if let Some(player) = board[1][1] {
-        if (board[0][0] == Some(player) && board[2][2] == Some(player))
-            || (board[0][2] == Some(player) && board[2][0] == Some(player))
-        {
-            return Some(player);
+// How a `MoveRecord` is saved: one optional field per kind of move, so saves
+// from before `MoveKind` still load.
+#[derive(Serialize, Deserialize)]
+struct SavedMove {
+    row: usize,
+    col: usize,
+    player: Player,
+    #[serde(default)]
+    from: Option<(usize, usize)>,
+    #[serde(default)]
+    symbol: Option<Player>,
+    #[serde(default)]
+    partner: Option<(usize, usize)>,
+    #[serde(default)]
+    size: Option<PieceSize>,
+    #[serde(default)]
+    swap: bool,
+}
+
+impl From<SavedMove> for MoveRecord {
+    fn from(saved: SavedMove) -> Self {
+        let kind = match saved {
+            SavedMove { swap: true, .. } => MoveKind::Swap,
+            SavedMove {
+                size: Some(size),
+                from,
+                ..
+            } => MoveKind::Gobblet { size, from },
+            SavedMove {
+                partner: Some(partner),
+                ..
+            } => MoveKind::Quantum { partner },
+            SavedMove {
+                symbol: Some(symbol),
+                ..
+            } => MoveKind::Wild { symbol },
+            SavedMove {
+                from: Some(from), ..
+            } => MoveKind::Slide { from },
+            SavedMove { .. } => MoveKind::Place,
+        };
+        MoveRecord {
+            row: saved.row,
+            col: saved.col,
+            player: saved.player,
+            kind,
         }
     }
+}
 
-    None
+impl From<MoveRecord> for SavedMove {
+    fn from(record: MoveRecord) -> Self {
+        let mut saved = SavedMove {
+            row: record.row,
+            col: record.col,
+            player: record.player,
+            from: record.vacated(),
+            symbol: None,
+            partner: None,
+            size: None,
+            swap: false,
+        };
+        match record.kind {
+            MoveKind::Place | MoveKind::Slide { .. } => {}
+            MoveKind::Wild { symbol } => saved.symbol = Some(symbol),
+            MoveKind::Quantum { partner } => saved.partner = Some(partner),
+            MoveKind::Gobblet { size, .. } => saved.size = Some(size),
+            MoveKind::Swap => saved.swap = true,
+        }
+        saved
+    }
 }
 
 // Represent the possible outcomes of the game
-#[derive(Debug, PartialEq, Clone, Copy, Eq)]
-#[allow(dead_code)]
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Serialize, Deserialize)]
 pub enum GameResult {
     Win(Player),
+    // Order and Chaos is won by a side rather than a symbol; the player who
+    // played it is kept alongside.
+    RoleWin(Role, Player),
+    // Quantum: won 1–½, the loser having made a line in the same collapse.
+    SplitWin(Player),
     Draw,
+    #[allow(dead_code)]
     InProgress,
 }
 
+impl GameResult {
+    // Returns the result as shown in exports.
+    pub fn label(self) -> String {
+        match self {
+            GameResult::Win(player) => format!("{} wins", player.to_char()),
+            GameResult::RoleWin(role, player) => {
+                format!("{} ({}) wins", role.label(), player.to_char())
+            }
+            GameResult::SplitWin(player) => format!("{} wins 1–½", player.to_char()),
+            GameResult::Draw => "Draw".to_string(),
+            GameResult::InProgress => "In progress".to_string(),
+        }
+    }
+}
+
+// A completed game, kept for the match history and exports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameRecord {
+    // Seconds since the Unix epoch when the game ended.
+    pub timestamp: u64,
+    pub game_mode: GameMode,
+    pub difficulty: Difficulty,
+    pub human_player: Player,
+    pub first_player: Player,
+    // Older saves predate other rule sets and were all standard games, on the
+    // board they kept under `board` (or on 3×3).
+    #[serde(default, alias = "board", deserialize_with = "rules_or_board")]
+    pub rules: GameRules,
+    // Older saves predate blocked cells and handicap marks.
+    #[serde(default)]
+    pub start: StartingPosition,
+    pub moves: Vec<MoveRecord>,
+    pub result: GameResult,
+}
+
+// Reads saved rules, or the standard game on the `BoardConfig` that older
+// saves kept in their place.
+pub fn rules_or_board<'de, D: Deserializer<'de>>(deserializer: D) -> Result<GameRules, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum SavedRules {
+        Board(BoardConfig),
+        Rules(GameRules),
+    }
+    Ok(match SavedRules::deserialize(deserializer)? {
+        SavedRules::Board(board) => GameRules {
+            board,
+            ..GameRules::default()
+        },
+        SavedRules::Rules(rules) => rules,
+    })
+}
+
 // Represents the difficulty level for the AI opponent.
-#[derive(Debug, PartialEq, Clone, Copy, Eq, Default)]
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Default, Serialize, Deserialize)]
 pub enum Difficulty {
-    #[allow(dead_code)]
     Easy,
     #[default]
     Medium,
     Hard,
 }
 
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+    // Returns the name shown in menus.
+    pub fn label(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+        }
+    }
+}
+
 // Represents the current mode of the game (e.g., Human vs Human, Human vs AI, AI vs AI).
-#[derive(Debug, PartialEq, Clone, Copy, Eq, Default)]
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Default, Serialize, Deserialize)]
 pub enum GameMode {
-    #[allow(dead_code)]
     HumanVsHuman,
     #[default]
     HumanVsAI,
-    #[allow(dead_code)]
     AIVsAI,
 }
 
+impl GameMode {
+    pub const ALL: [GameMode; 3] = [
+        GameMode::HumanVsHuman,
+        GameMode::HumanVsAI,
+        GameMode::AIVsAI,
+    ];
+
+    // Returns the name shown in menus.
+    pub fn label(self) -> &'static str {
+        match self {
+            GameMode::HumanVsHuman => "Human vs Human",
+            GameMode::HumanVsAI => "Human vs AI",
+            GameMode::AIVsAI => "AI vs AI",
+        }
+    }
+}
+
+// Color scheme for the board and UI text.
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Default, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 2] = [Theme::Light, Theme::Dark];
+
+    // Returns the name shown in menus.
+    pub fn label(self) -> &'static str {
+        match self {
+            Theme::Light => "Light",
+            Theme::Dark => "Dark",
+        }
+    }
+}
+
 // Helper function to check if the board is full (for draw detection)
 pub fn is_board_full(board: &Board) -> bool {
-    board
-        .iter()
-        .all(|row| row.iter().all(|cell| cell.is_some()))
+    board.empty_cells().next().is_none()
 }
 
 // Get the current game result
@@ -0,0 +1,372 @@
+use std::cmp::Reverse;
+use std::sync::OnceLock;
+
+use crate::ai_logic::evaluate_board;
+use crate::config::ULTIMATE_HARD_AI_DEPTH;
+use crate::search::{Game, Outcome};
+use crate::types::{
+    check_winner, find_winning_line as find_meta_line, is_board_full, Board, BoardConfig,
+    GameResult, Player, WinningLine,
+};
+
+// Ultimate tic-tac-toe: a 3×3 grid of 3×3 sub-boards, stored as one 9×9 `Board`.
+// Sub-board `b` covers rows 3 * (b / 3).. and columns 3 * (b % 3)... A mark in
+// cell (r, c) of any sub-board sends the opponent to sub-board 3 * r + c, unless
+// that one is already decided, in which case they may play in any open sub-board.
+// Winning a sub-board claims the matching cell of a 3×3 meta-board, which is won
+// with the ordinary three-in-a-row rules.
+
+pub const ULTIMATE_BOARD: BoardConfig = BoardConfig::new(9, 3);
+
+// Sub-boards per side of the meta-board, and cells per side of a sub-board.
+const SUB: usize = 3;
+const SUB_BOARDS: usize = SUB * SUB;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubBoardStatus {
+    Open,
+    Won(Player),
+    Drawn,
+}
+
+// Cloning a cached board is much cheaper than building its winning lines again.
+fn empty_classic_board() -> Board {
+    static EMPTY: OnceLock<Board> = OnceLock::new();
+    EMPTY
+        .get_or_init(|| Board::new(BoardConfig::CLASSIC))
+        .clone()
+}
+
+// Which sub-board a cell of the 9×9 board belongs to.
+pub fn sub_board_of(row: usize, col: usize) -> usize {
+    (row / SUB) * SUB + col / SUB
+}
+
+// Top-left cell of a sub-board on the 9×9 board.
+pub fn sub_board_origin(index: usize) -> (usize, usize) {
+    ((index / SUB) * SUB, (index % SUB) * SUB)
+}
+
+// A copy of one sub-board as a classic 3×3 board.
+pub fn sub_board(board: &Board, index: usize) -> Board {
+    let (top, left) = sub_board_origin(index);
+    let mut sub = empty_classic_board();
+    for row in 0..SUB {
+        for col in 0..SUB {
+            sub[row][col] = board[top + row][left + col];
+        }
+    }
+    sub
+}
+
+pub fn sub_board_status(board: &Board, index: usize) -> SubBoardStatus {
+    let sub = sub_board(board, index);
+    if let Some(winner) = check_winner(&sub) {
+        SubBoardStatus::Won(winner)
+    } else if is_board_full(&sub) {
+        SubBoardStatus::Drawn
+    } else {
+        SubBoardStatus::Open
+    }
+}
+
+pub fn sub_board_statuses(board: &Board) -> [SubBoardStatus; SUB_BOARDS] {
+    std::array::from_fn(|index| sub_board_status(board, index))
+}
+
+// The meta-board: each cell holds the winner of the matching sub-board.
+fn meta_board_from(statuses: &[SubBoardStatus; SUB_BOARDS]) -> Board {
+    let mut meta = empty_classic_board();
+    for (index, status) in statuses.iter().enumerate() {
+        if let SubBoardStatus::Won(player) = status {
+            meta[index / SUB][index % SUB] = Some(*player);
+        }
+    }
+    meta
+}
+
+pub fn meta_board(board: &Board) -> Board {
+    meta_board_from(&sub_board_statuses(board))
+}
+
+fn target_from(
+    statuses: &[SubBoardStatus; SUB_BOARDS],
+    last_move: Option<(usize, usize)>,
+) -> Option<usize> {
+    let (row, col) = last_move?;
+    let target = (row % SUB) * SUB + col % SUB;
+    (statuses[target] == SubBoardStatus::Open).then_some(target)
+}
+
+// The sub-board the player to move is sent to, or None if they may play in
+// any open sub-board.
+pub fn target_sub_board(board: &Board, last_move: Option<(usize, usize)>) -> Option<usize> {
+    target_from(&sub_board_statuses(board), last_move)
+}
+
+fn legal_moves_from(
+    board: &Board,
+    statuses: &[SubBoardStatus; SUB_BOARDS],
+    last_move: Option<(usize, usize)>,
+) -> Vec<(usize, usize)> {
+    if check_winner(&meta_board_from(statuses)).is_some() {
+        return Vec::new();
+    }
+
+    let boards: Vec<usize> = match target_from(statuses, last_move) {
+        Some(target) => vec![target],
+        None => (0..SUB_BOARDS)
+            .filter(|&index| statuses[index] == SubBoardStatus::Open)
+            .collect(),
+    };
+
+    let mut moves = Vec::new();
+    for index in boards {
+        let (top, left) = sub_board_origin(index);
+        for row in top..top + SUB {
+            for col in left..left + SUB {
+                if board[row][col].is_none() {
+                    moves.push((row, col));
+                }
+            }
+        }
+    }
+    moves
+}
+
+// Every cell the player to move may play in.
+pub fn legal_moves(board: &Board, last_move: Option<(usize, usize)>) -> Vec<(usize, usize)> {
+    legal_moves_from(board, &sub_board_statuses(board), last_move)
+}
+
+pub fn is_legal_move(
+    board: &Board,
+    last_move: Option<(usize, usize)>,
+    row: usize,
+    col: usize,
+) -> bool {
+    legal_moves(board, last_move).contains(&(row, col))
+}
+
+// The won line of the meta-board, reported as the centre cells of its
+// three sub-boards so the highlight runs across the whole board.
+pub fn find_winning_line(board: &Board) -> Option<WinningLine> {
+    let line = find_meta_line(&meta_board(board))?;
+    Some(WinningLine {
+        cells: line
+            .cells
+            .iter()
+            .map(|&(row, col)| (row * SUB + 1, col * SUB + 1))
+            .collect(),
+        ..line
+    })
+}
+
+// Won by whoever completes a meta-board line (before any misère reversal);
+// drawn once nobody can move.
+pub fn game_result(board: &Board, last_move: Option<(usize, usize)>) -> GameResult {
+    let statuses = sub_board_statuses(board);
+    if let Some(winner) = check_winner(&meta_board_from(&statuses)) {
+        GameResult::Win(winner)
+    } else if legal_moves_from(board, &statuses, last_move).is_empty() {
+        GameResult::Draw
+    } else {
+        GameResult::InProgress
+    }
+}
+
+// Whether a mark of `player` in the cell completes a line of its sub-board.
+fn completes_sub_line(board: &Board, (row, col): (usize, usize), player: Player) -> bool {
+    let (top, left) = sub_board_origin(sub_board_of(row, col));
+    let (local_row, local_col) = (row - top, col - left);
+    let mut lines = vec![
+        [(local_row, 0), (local_row, 1), (local_row, 2)],
+        [(0, local_col), (1, local_col), (2, local_col)],
+    ];
+    if local_row == local_col {
+        lines.push([(0, 0), (1, 1), (2, 2)]);
+    }
+    if local_row + local_col == SUB - 1 {
+        lines.push([(0, 2), (1, 1), (2, 0)]);
+    }
+    lines.iter().any(|line| {
+        line.iter()
+            .filter(|&&cell| cell != (local_row, local_col))
+            .all(|&(r, c)| board[top + r][left + c] == Some(player))
+    })
+}
+
+// How promising a move looks to `player`, so the search tries the best
+// first: winning its sub-board, then blocking the opponent there, then the
+// centre and corners. Sending the opponent to a decided sub-board sets them
+// free to play anywhere, which counts against the move.
+fn move_priority(
+    board: &Board,
+    statuses: &[SubBoardStatus; SUB_BOARDS],
+    (row, col): (usize, usize),
+    player: Player,
+) -> i32 {
+    let mut priority = 0;
+    if completes_sub_line(board, (row, col), player) {
+        priority += 1000;
+    } else if completes_sub_line(board, (row, col), player.opposite()) {
+        priority += 500;
+    }
+    priority += match (row % SUB, col % SUB) {
+        (1, 1) => 3,
+        (0 | 2, 0 | 2) => 2,
+        _ => 1,
+    };
+    let target = (row % SUB) * SUB + col % SUB;
+    if statuses[target] != SubBoardStatus::Open {
+        priority -= 100;
+    }
+    priority
+}
+
+// A won sub-board, and one or two won sub-boards in a meta line still open.
+const SUB_BOARD_SCORE: i32 = 100;
+const ONE_IN_META_LINE_SCORE: i32 = 20;
+const TWO_IN_META_LINE_SCORE: i32 = 200;
+
+// Heuristic score from Player::O's point of view: won sub-boards, meta-board
+// lines still open to one player, and the standard heuristic on open sub-boards.
+fn evaluate(board: &Board, statuses: &[SubBoardStatus; SUB_BOARDS]) -> i32 {
+    let mut score = 0;
+
+    for (index, status) in statuses.iter().enumerate() {
+        score += match status {
+            SubBoardStatus::Won(Player::O) => SUB_BOARD_SCORE,
+            SubBoardStatus::Won(Player::X | Player::Triangle) => -SUB_BOARD_SCORE,
+            SubBoardStatus::Drawn => 0,
+            SubBoardStatus::Open => evaluate_board(&sub_board(board, index)),
+        };
+    }
+
+    let meta = meta_board_from(statuses);
+    for line in meta.lines() {
+        let mut o_count = 0;
+        let mut x_count = 0;
+        let mut blocked = false;
+        for &(row, col) in line.cells.iter() {
+            match statuses[row * SUB + col] {
+                SubBoardStatus::Won(Player::O) => o_count += 1,
+                SubBoardStatus::Won(Player::X | Player::Triangle) => x_count += 1,
+                SubBoardStatus::Drawn => blocked = true,
+                SubBoardStatus::Open => {}
+            }
+        }
+        if blocked {
+            continue;
+        }
+
+        score += match (o_count, x_count) {
+            (1, 0) => ONE_IN_META_LINE_SCORE,
+            (2, 0) => TWO_IN_META_LINE_SCORE,
+            (0, 1) => -ONE_IN_META_LINE_SCORE,
+            (0, 2) => -TWO_IN_META_LINE_SCORE,
+            _ => 0,
+        };
+    }
+
+    score
+}
+
+// An Ultimate game as the shared search sees it, with O maximizing. Only the
+// sub-board of the last move can change status, so `statuses` is updated for
+// that sub-board alone and restored when the move is taken back.
+pub struct UltimateGame {
+    board: Board,
+    statuses: [SubBoardStatus; SUB_BOARDS],
+    // Cells played, most recent last, each with the status its sub-board had
+    // before.
+    played: Vec<((usize, usize), SubBoardStatus)>,
+    last_move: Option<(usize, usize)>,
+    to_move: Player,
+    misere: bool,
+}
+
+impl UltimateGame {
+    pub fn new(
+        board: &Board,
+        last_move: Option<(usize, usize)>,
+        to_move: Player,
+        misere: bool,
+    ) -> Self {
+        UltimateGame {
+            board: board.clone(),
+            statuses: sub_board_statuses(board),
+            played: Vec::new(),
+            last_move,
+            to_move,
+            misere,
+        }
+    }
+
+    fn last_move(&self) -> Option<(usize, usize)> {
+        match self.played.last() {
+            Some(&(cell, _)) => Some(cell),
+            None => self.last_move,
+        }
+    }
+}
+
+impl Game for UltimateGame {
+    type Move = (usize, usize);
+
+    const NAME: &'static str = "Ultimate";
+    const HARD_DEPTH: i32 = ULTIMATE_HARD_AI_DEPTH;
+
+    fn maximizing(&self) -> bool {
+        self.to_move == Player::O
+    }
+
+    fn legal_moves(&self) -> Vec<(usize, usize)> {
+        legal_moves_from(&self.board, &self.statuses, self.last_move())
+    }
+
+    // Best-looking moves first, which helps alpha-beta prune early.
+    fn search_moves(&self) -> Vec<(usize, usize)> {
+        let mut moves = self.legal_moves();
+        moves.sort_by_key(|&cell| {
+            Reverse(move_priority(
+                &self.board,
+                &self.statuses,
+                cell,
+                self.to_move,
+            ))
+        });
+        moves
+    }
+
+    fn apply(&mut self, (row, col): (usize, usize)) {
+        let index = sub_board_of(row, col);
+        self.played.push(((row, col), self.statuses[index]));
+        self.board[row][col] = Some(self.to_move);
+        self.statuses[index] = sub_board_status(&self.board, index);
+        self.to_move = self.to_move.opposite();
+    }
+
+    fn undo(&mut self, (row, col): (usize, usize)) {
+        if let Some((_, previous_status)) = self.played.pop() {
+            self.statuses[sub_board_of(row, col)] = previous_status;
+        }
+        self.board[row][col] = None;
+        self.to_move = self.to_move.opposite();
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        if let Some(winner) = check_winner(&meta_board_from(&self.statuses)) {
+            return Some(Outcome::Win(winner == Player::O));
+        }
+        self.legal_moves().is_empty().then_some(Outcome::Draw)
+    }
+
+    fn evaluate(&self) -> i32 {
+        evaluate(&self.board, &self.statuses)
+    }
+
+    fn misere(&self) -> bool {
+        self.misere
+    }
+}
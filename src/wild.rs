@@ -0,0 +1,114 @@
+use crate::ai_logic::{candidate_moves, depth_cap};
+use crate::config::WILD_HARD_AI_DEPTH;
+use crate::search::{Game, Outcome, SearchBoard};
+use crate::types::{find_winning_line, Board, Player, WinningLine};
+
+// Wild tic-tac-toe: on each turn the player to move places either an X or an
+// O, and whoever completes a line of either symbol wins. The board stores
+// symbols, so who made a move comes from the move itself, not from the cell.
+
+// A move: the cell and the symbol placed there.
+pub type WildMove = ((usize, usize), Player);
+
+// Every candidate cell with both symbols.
+pub fn legal_moves(board: &Board) -> Vec<WildMove> {
+    candidate_moves(board)
+        .into_iter()
+        .flat_map(|cell| Player::ALL.map(|symbol| (cell, symbol)))
+        .collect()
+}
+
+// The completed line, of either symbol, credited to `mover`: whoever moved
+// last completed it.
+pub fn winning_line(board: &Board, mover: Player) -> Option<WinningLine> {
+    let line = find_winning_line(board)?;
+    Some(WinningLine {
+        player: mover,
+        ..line
+    })
+}
+
+// Whether some line is one mark short of complete. Either symbol counts, so
+// whoever is to move could finish it.
+fn has_open_line(board: &Board) -> bool {
+    board.lines().iter().any(|line| {
+        let mut empty = 0;
+        let mut symbol = None;
+        for &(row, col) in line.cells.iter() {
+            match board[row][col] {
+                None => empty += 1,
+                Some(mark) if symbol.is_none() => symbol = Some(mark),
+                Some(mark) if symbol != Some(mark) => return false,
+                Some(_) => {}
+            }
+        }
+        empty == 1
+    })
+}
+
+// A wild game as the shared search sees it, with O maximizing (O being a
+// player here, not a symbol). Both symbols serve both players alike, so
+// heuristics have nothing to go on; instead a line one mark short of complete
+// wins for whoever is to move, as they finish it at once. Searched like the
+// standard game but one ply shallower, since every cell offers two moves.
+pub struct WildGame {
+    search_board: SearchBoard,
+    to_move: Player,
+    misere: bool,
+}
+
+impl WildGame {
+    pub fn new(board: &Board, to_move: Player, misere: bool) -> Self {
+        WildGame {
+            search_board: SearchBoard::new(board),
+            to_move,
+            misere,
+        }
+    }
+}
+
+impl Game for WildGame {
+    type Move = WildMove;
+
+    const NAME: &'static str = "Wild";
+    const HARD_DEPTH: i32 = WILD_HARD_AI_DEPTH;
+
+    fn maximizing(&self) -> bool {
+        self.to_move == Player::O
+    }
+
+    fn legal_moves(&self) -> Vec<WildMove> {
+        legal_moves(&self.search_board.board)
+    }
+
+    fn apply(&mut self, (cell, symbol): WildMove) {
+        self.search_board.place(cell, symbol);
+        self.to_move = self.to_move.opposite();
+    }
+
+    fn undo(&mut self, (cell, _): WildMove) {
+        self.search_board.take_back(cell);
+        self.to_move = self.to_move.opposite();
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        // The last move was made by the player who is not to move now
+        if self.search_board.completed_line().is_some() {
+            return Some(Outcome::Win(self.to_move != Player::O));
+        }
+        if self.search_board.is_full() {
+            return Some(Outcome::Draw);
+        }
+        // Under misère rules nobody wants to finish the line
+        (!self.misere && has_open_line(&self.search_board.board))
+            .then_some(Outcome::Win(self.to_move == Player::O))
+    }
+
+    fn misere(&self) -> bool {
+        self.misere
+    }
+
+    fn depth_cap(&self) -> i32 {
+        (depth_cap(&self.search_board.board) - 1).max(1)
+    }
+}